@@ -0,0 +1,231 @@
+use std::collections::HashSet;
+
+use eframe::egui::{Key, TextEdit, Ui};
+use newsapi::{Category, NewsApiError, ParseCategoryError};
+
+use crate::{
+    headlines::{Headlines, RED},
+    i18n::t,
+    Fetched, Msg, Query,
+};
+
+/// Comma separated category names, e.g. "science, health". Empty names are skipped.
+pub fn parse_categories(input: &str) -> Result<Vec<Category>, ParseCategoryError> {
+    let mut categories = Vec::new();
+    for name in input.split(',').filter(|name| !name.trim().is_empty()) {
+        let category = name.parse()?;
+        if !categories.contains(&category) {
+            categories.push(category);
+        }
+    }
+    Ok(categories)
+}
+
+/// The requests behind `query`, one per category since newsapi takes a single category
+/// per request. Only plain top headlines blend, searches and the other feeds ignore it.
+pub(crate) fn blend_queries(query: &Query) -> Vec<Query> {
+    let blends = query.search.is_none() && query.home.is_none() && query.sources.is_none();
+    let mut queries = vec![query.clone()];
+    if blends {
+        for category in query.blend.iter().filter(|c| **c != query.category) {
+            let mut blended = query.clone();
+            blended.category = *category;
+            queries.push(blended);
+        }
+    }
+    queries
+}
+
+/// Merges the results for each category into one feed, taking an article from each in
+/// turn and dropping ones another category already brought. Returns the categories that
+/// failed alongside it, and only fails as a whole when every category did.
+pub(crate) fn merge_blend(
+    results: Vec<(Category, Result<Fetched, NewsApiError>)>,
+) -> (Result<Fetched, NewsApiError>, Vec<Category>) {
+    if results.len() == 1 {
+        let (_, result) = results.into_iter().next().unwrap();
+        return (result, Vec::new());
+    }
+    let mut failed = Vec::new();
+    let mut first_error = None;
+    let mut total_results = 0;
+    let mut not_modified = true;
+    let mut feeds = Vec::new();
+    for (category, result) in results {
+        match result {
+            Ok(fetched) => {
+                total_results += fetched.total_results;
+                not_modified &= fetched.not_modified;
+                feeds.push(fetched.articles.into_iter());
+            }
+            Err(e) => {
+                tracing::warn!("failed fetching {} for the blended feed: {}", category, e);
+                failed.push(category);
+                first_error.get_or_insert(e);
+            }
+        }
+    }
+    if feeds.is_empty() {
+        if let Some(e) = first_error {
+            return (Err(e), Vec::new());
+        }
+    }
+
+    let mut seen = HashSet::new();
+    let mut articles = Vec::new();
+    loop {
+        let mut took = false;
+        for feed in &mut feeds {
+            if let Some(article) = feed.next() {
+                took = true;
+                if seen.insert(article.id().to_string()) {
+                    articles.push(article);
+                }
+            }
+        }
+        if !took {
+            break;
+        }
+    }
+    let fetched = Fetched {
+        total_results,
+        articles,
+        // unchanged only if every category is, and none of them failed this time
+        not_modified: not_modified && failed.is_empty(),
+    };
+    (Ok(fetched), failed)
+}
+
+impl Headlines {
+    pub fn set_blend(&mut self, categories: Vec<Category>) {
+        if self.config.blend_categories == categories {
+            return;
+        }
+        self.config.blend_categories = categories.clone();
+        self.reset_feed();
+        self.send(Msg::BlendChanged(categories));
+    }
+
+    /// Adds `category` to the feed alongside the selected one, or takes it out again.
+    pub fn toggle_blended(&mut self, category: Category) {
+        let mut categories = self.config.blend_categories.clone();
+        match categories.iter().position(|c| *c == category) {
+            Some(i) => {
+                categories.remove(i);
+            }
+            None => categories.push(category),
+        }
+        self.set_blend(categories);
+    }
+
+    pub(crate) fn is_blended(&self) -> bool {
+        self.config.blend_categories.iter().any(|c| *c != self.config.category)
+    }
+
+    pub fn render_blend_settings(&mut self, ui: &mut Ui) {
+        let names: Vec<&str> = self.config.blend_categories.iter().map(|c| c.name()).collect();
+        let names = names.join(", ");
+        // edits are kept apart from the config until Enter applies them
+        let mut input = self.blend_input.take().unwrap_or_else(|| names.clone());
+        ui.horizontal(|ui| {
+            ui.label(t("blend_categories"));
+            let response = ui.add(TextEdit::singleline(&mut input).hint_text(t("blend_hint")));
+            let response = response.on_hover_text(t("blend_tab_hint"));
+            if response.lost_focus() && ui.input().key_pressed(Key::Enter) {
+                match parse_categories(&input) {
+                    Ok(categories) => {
+                        self.blend_error = None;
+                        self.set_blend(categories);
+                        return;
+                    }
+                    Err(e) => self.blend_error = Some(e.to_string()),
+                }
+            }
+            if input != names {
+                self.blend_input = Some(input);
+            }
+        });
+        if let Some(e) = &self.blend_error {
+            ui.colored_label(RED, e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::NewsCardData;
+
+    #[test]
+    fn categories_are_parsed_once_each() {
+        let parsed = parse_categories("science, Health,,  ,SCIENCE").unwrap();
+        assert_eq!(parsed, [Category::Science, Category::Health]);
+        assert_eq!(parse_categories("").unwrap(), []);
+    }
+
+    #[test]
+    fn an_unknown_category_fails_the_whole_list() {
+        let e = parse_categories("science, politics").unwrap_err();
+        assert_eq!(e.to_string(), r#"unknown category " politics""#);
+    }
+
+    #[test]
+    fn the_selected_category_isnt_fetched_twice() {
+        let mut query = Query::new(Category::General);
+        query.blend = vec![Category::Science, Category::General, Category::Health];
+        let categories: Vec<Category> = blend_queries(&query).iter().map(|q| q.category).collect();
+        assert_eq!(categories, [Category::General, Category::Science, Category::Health]);
+        query.search = Some("rust".to_string());
+        assert_eq!(blend_queries(&query).len(), 1);
+    }
+
+    fn fetched(titles: &[&str]) -> Result<Fetched, NewsApiError> {
+        let articles = titles
+            .iter()
+            .map(|title| NewsCardData::new(title, &format!("https://example.com/{}", title)))
+            .collect();
+        Ok(Fetched {
+            total_results: titles.len() as u64,
+            articles,
+            not_modified: false,
+        })
+    }
+
+    #[test]
+    fn feeds_are_merged_in_turn_without_repeats() {
+        let results = vec![
+            (Category::General, fetched(&["g1", "g2", "g3"])),
+            (Category::Science, fetched(&["s1", "g2"])),
+            (Category::Health, fetched(&["h1"])),
+        ];
+        let (merged, failed) = merge_blend(results);
+        let merged = merged.unwrap();
+        let titles: Vec<&str> = merged.articles.iter().map(|a| a.title.as_str()).collect();
+        assert_eq!(titles, ["g1", "s1", "h1", "g2", "g3"]);
+        assert_eq!(merged.total_results, 6);
+        assert!(failed.is_empty());
+    }
+
+    #[test]
+    fn failed_categories_are_listed_beside_the_rest() {
+        let results = vec![
+            (Category::General, fetched(&["g1"])),
+            (Category::Science, Err(NewsApiError::RateLimited)),
+            (Category::Health, Err(NewsApiError::BadApiKey)),
+        ];
+        let (merged, failed) = merge_blend(results);
+        assert_eq!(merged.unwrap().articles.len(), 1);
+        assert_eq!(failed, [Category::Science, Category::Health]);
+    }
+
+    #[test]
+    fn the_first_error_is_kept_when_every_category_fails() {
+        let results = vec![
+            (Category::General, Err(NewsApiError::RateLimited)),
+            (Category::Science, Err(NewsApiError::BadApiKey)),
+        ];
+        let (merged, failed) = merge_blend(results);
+        assert!(matches!(merged, Err(NewsApiError::RateLimited)));
+        assert!(failed.is_empty());
+    }
+}
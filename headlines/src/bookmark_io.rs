@@ -0,0 +1,225 @@
+use std::{
+    collections::HashSet,
+    sync::mpsc::{channel, Receiver, Sender},
+};
+
+use eframe::egui::Context;
+use serde::{Deserialize, Serialize};
+
+use crate::{bookmarks::Bookmark, headlines::Headlines, i18n::tf};
+
+/// Bumped whenever the export format changes incompatibly.
+const BOOKMARKS_FILE_VERSION: u32 = 1;
+const EXPORT_FILE_NAME: &str = "bookmarks.json";
+
+/// What an exported bookmarks file looks like.
+#[derive(Serialize, Deserialize)]
+struct BookmarksFile<T> {
+    version: u32,
+    bookmarks: Vec<T>,
+}
+
+pub struct ImportReport {
+    pub added: usize,
+    pub skipped: usize,
+}
+
+pub fn export_json(bookmarks: &[Bookmark]) -> String {
+    let file = BookmarksFile {
+        version: BOOKMARKS_FILE_VERSION,
+        bookmarks: bookmarks.iter().collect(),
+    };
+    serde_json::to_string_pretty(&file).unwrap_or_default()
+}
+
+/// Merges the bookmarks in an exported file into `existing`. Entries that don't
+/// parse, or whose id is already bookmarked, are skipped rather than failing the import.
+pub fn merge_import(existing: &mut Vec<Bookmark>, json: &[u8]) -> Result<ImportReport, String> {
+    let file: BookmarksFile<serde_json::Value> =
+        serde_json::from_slice(json).map_err(|e| format!("not a bookmarks file: {}", e))?;
+    if file.version != BOOKMARKS_FILE_VERSION {
+        return Err(format!("unsupported bookmarks file version {}", file.version));
+    }
+    let mut ids: HashSet<String> = existing.iter().map(|b| b.id.clone()).collect();
+    let mut report = ImportReport {
+        added: 0,
+        skipped: 0,
+    };
+    for value in file.bookmarks {
+        match serde_json::from_value::<Bookmark>(value) {
+            Ok(b) if !b.id.is_empty() && ids.insert(b.id.clone()) => {
+                existing.push(b);
+                report.added += 1;
+            }
+            _ => report.skipped += 1,
+        }
+    }
+    Ok(report)
+}
+
+/// Carries picked files back from the file dialog, which runs off the UI thread.
+pub struct BookmarkFiles {
+    tx: Sender<Vec<u8>>,
+    rx: Receiver<Vec<u8>>,
+}
+
+impl Default for BookmarkFiles {
+    fn default() -> Self {
+        let (tx, rx) = channel();
+        BookmarkFiles { tx, rx }
+    }
+}
+
+impl Headlines {
+    pub fn export_bookmarks(&self) {
+        save_file(export_json(&self.config.bookmarks));
+    }
+
+    pub fn import_bookmarks(&self) {
+        pick_file(self.bookmark_files.tx.clone(), self.egui_ctx.clone());
+    }
+
+    /// Merges any file picked since the last frame and reports the outcome.
+    pub fn poll_bookmark_imports(&mut self, now: f64) {
+        while let Ok(json) = self.bookmark_files.rx.try_recv() {
+            let msg = match merge_import(&mut self.config.bookmarks, &json) {
+                Ok(report) => {
+                    self.evict_bookmarks();
+                    tf("import_report", &[&report.added, &report.skipped])
+                }
+                Err(e) => {
+                    tracing::warn!("failed importing bookmarks: {}", e);
+                    tf("import_failed", &[&e])
+                }
+            };
+            self.show_toast(&msg, now);
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn save_file(json: String) {
+    std::thread::spawn(move || {
+        let path = rfd::FileDialog::new()
+            .set_file_name(EXPORT_FILE_NAME)
+            .add_filter("JSON", &["json"])
+            .save_file();
+        if let Some(path) = path {
+            if let Err(e) = std::fs::write(&path, json) {
+                tracing::error!("failed writing {}: {}", path.display(), e);
+            }
+        }
+    });
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn pick_file(tx: Sender<Vec<u8>>, repaint: Option<Context>) {
+    std::thread::spawn(move || {
+        let path = rfd::FileDialog::new()
+            .add_filter("JSON", &["json"])
+            .pick_file();
+        if let Some(path) = path {
+            match std::fs::read(&path) {
+                Ok(bytes) => {
+                    let _ = tx.send(bytes);
+                    if let Some(ctx) = repaint {
+                        ctx.request_repaint();
+                    }
+                }
+                Err(e) => tracing::error!("failed reading {}: {}", path.display(), e),
+            }
+        }
+    });
+}
+
+/// Browsers can't be handed a path to write to, so the export is offered as a download.
+#[cfg(target_arch = "wasm32")]
+fn save_file(json: String) {
+    use eframe::wasm_bindgen::JsCast;
+
+    let download = || -> Option<()> {
+        let parts = js_sys::Array::of1(&json.into());
+        let mut options = web_sys::BlobPropertyBag::new();
+        options.type_("application/json");
+        let blob = web_sys::Blob::new_with_str_sequence_and_options(&parts, &options).ok()?;
+        let url = web_sys::Url::create_object_url_with_blob(&blob).ok()?;
+        let document = web_sys::window()?.document()?;
+        let anchor: web_sys::HtmlAnchorElement =
+            document.create_element("a").ok()?.dyn_into().ok()?;
+        anchor.set_href(&url);
+        anchor.set_download(EXPORT_FILE_NAME);
+        anchor.click();
+        web_sys::Url::revoke_object_url(&url).ok()
+    };
+    if download().is_none() {
+        tracing::error!("failed starting bookmarks download");
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+fn pick_file(tx: Sender<Vec<u8>>, repaint: Option<Context>) {
+    wasm_bindgen_futures::spawn_local(async move {
+        let file = rfd::AsyncFileDialog::new()
+            .add_filter("JSON", &["json"])
+            .pick_file()
+            .await;
+        if let Some(file) = file {
+            let _ = tx.send(file.read().await);
+            if let Some(ctx) = repaint {
+                ctx.request_repaint();
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bookmark(id: &str) -> Bookmark {
+        Bookmark {
+            id: id.to_string(),
+            title: format!("Title {}", id),
+            url: format!("https://example.com/{}", id),
+            description: None,
+            source: String::new(),
+            published_at: None,
+            added_at: None,
+        }
+    }
+
+    #[test]
+    fn other_file_versions_are_refused() {
+        let mut existing = vec![bookmark("a")];
+        let json = r#"{"version": 2, "bookmarks": []}"#;
+        let e = merge_import(&mut existing, json.as_bytes()).err().unwrap();
+        assert_eq!(e, "unsupported bookmarks file version 2");
+        assert!(merge_import(&mut existing, b"[]").is_err());
+        assert_eq!(existing.len(), 1);
+    }
+
+    #[test]
+    fn an_export_imports_back_without_duplicates() {
+        let json = export_json(&[bookmark("a"), bookmark("b")]);
+        let mut existing = vec![bookmark("b")];
+        let report = merge_import(&mut existing, json.as_bytes()).unwrap();
+        assert_eq!((report.added, report.skipped), (1, 1));
+        let ids: Vec<&str> = existing.iter().map(|b| b.id.as_str()).collect();
+        assert_eq!(ids, ["b", "a"]);
+    }
+
+    #[test]
+    fn broken_and_repeated_entries_are_counted_as_skipped() {
+        let json = r#"{"version": 1, "bookmarks": [
+            {"id": "a", "title": "A", "url": "https://example.com/a", "description": null},
+            {"id": "a", "title": "A again", "url": "https://example.com/a", "description": null},
+            {"id": "", "title": "No id", "url": "https://example.com/", "description": null},
+            {"title": "Missing fields"},
+            "not even an object"
+        ]}"#;
+        let mut existing = Vec::new();
+        let report = merge_import(&mut existing, json.as_bytes()).unwrap();
+        assert_eq!((report.added, report.skipped), (1, 4));
+        assert_eq!(existing[0].title, "A");
+    }
+}
@@ -0,0 +1,259 @@
+use eframe::{
+    egui::{Button, ComboBox, Hyperlink, Layout, RichText, Separator, TextEdit, Ui},
+    emath::Align,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    headlines::{Headlines, AMBER},
+    i18n::{t, tf},
+    icons::{icon, plain},
+    numbers::format_count,
+    text::{is_web_url, matches_filter},
+    NewsCardData, PADDING,
+};
+
+/// Default cap on stored bookmarks, so the persisted config can't grow without bound.
+pub const MAX_BOOKMARKS: usize = 500;
+/// Share of the cap at which the bookmarks view starts warning.
+const BOOKMARKS_WARN_RATIO: f32 = 0.9;
+
+/// An article saved from the feed, kept in the persisted config.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Bookmark {
+    pub id: String,
+    pub title: String,
+    pub url: String,
+    pub description: Option<String>,
+    #[serde(default)]
+    pub source: String,
+    #[serde(default)]
+    pub published_at: Option<String>,
+    /// Unix seconds when the bookmark was saved; missing on bookmarks saved before sorting.
+    #[serde(default)]
+    pub added_at: Option<f64>,
+}
+
+impl From<&NewsCardData> for Bookmark {
+    fn from(a: &NewsCardData) -> Self {
+        Bookmark {
+            id: a.id().to_string(),
+            title: a.title.clone(),
+            url: a.url.clone(),
+            description: a.description.clone(),
+            source: a.source.clone(),
+            published_at: a.published_at.clone(),
+            added_at: Some(unix_time()),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BookmarkSort {
+    #[default]
+    Added,
+    Published,
+    Source,
+}
+
+impl BookmarkSort {
+    pub const ALL: [BookmarkSort; 3] = [
+        BookmarkSort::Added,
+        BookmarkSort::Published,
+        BookmarkSort::Source,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            BookmarkSort::Added => t("sort_added"),
+            BookmarkSort::Published => t("sort_published"),
+            BookmarkSort::Source => t("sort_source"),
+        }
+    }
+
+    /// Stable sort, newest first for the dates and alphabetical for sources.
+    fn sort(&self, bookmarks: &mut [&Bookmark]) {
+        match self {
+            BookmarkSort::Added => bookmarks.sort_by(|a, b| {
+                let (a, b) = (a.added_at.unwrap_or(0.), b.added_at.unwrap_or(0.));
+                b.total_cmp(&a)
+            }),
+            // ISO 8601 timestamps order correctly as strings
+            BookmarkSort::Published => {
+                bookmarks.sort_by(|a, b| b.published_at.cmp(&a.published_at))
+            }
+            BookmarkSort::Source => bookmarks.sort_by_key(|b| b.source.to_lowercase()),
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn unix_time() -> f64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs_f64())
+        .unwrap_or_default()
+}
+
+#[cfg(target_arch = "wasm32")]
+fn unix_time() -> f64 {
+    js_sys::Date::now() / 1000.
+}
+
+impl Headlines {
+    pub fn is_bookmarked(&self, id: &str) -> bool {
+        self.config.bookmarks.iter().any(|b| b.id == id)
+    }
+
+    pub fn toggle_bookmark(&mut self, id: &str) {
+        if self.is_bookmarked(id) {
+            self.config.bookmarks.retain(|b| b.id != id);
+            return;
+        }
+        self.restore_deferred(id);
+        if let Some(a) = self.articles.iter().find(|a| a.id() == id) {
+            self.config.bookmarks.push(Bookmark::from(a));
+            self.evict_bookmarks();
+        }
+    }
+
+    /// Drops the oldest-added bookmarks until the cap is met. Bookmarks saved before
+    /// `added_at` existed count as the oldest.
+    pub fn evict_bookmarks(&mut self) {
+        let max = self.config.max_bookmarks;
+        let bookmarks = &mut self.config.bookmarks;
+        if max == 0 || bookmarks.len() <= max {
+            return;
+        }
+        let evicted = bookmarks.len() - max;
+        let mut by_age: Vec<(f64, usize)> = bookmarks
+            .iter()
+            .enumerate()
+            .map(|(i, b)| (b.added_at.unwrap_or(0.), i))
+            .collect();
+        by_age.sort_by(|a, b| a.0.total_cmp(&b.0));
+        let mut drop = vec![false; bookmarks.len()];
+        for (_, i) in &by_age[..evicted] {
+            drop[*i] = true;
+        }
+        let mut drop = drop.into_iter();
+        bookmarks.retain(|_| !drop.next().unwrap_or(false));
+        tracing::info!("evicted {} oldest bookmarks", evicted);
+    }
+
+    pub fn render_bookmarks(&mut self, ui: &mut Ui) {
+        self.poll_bookmark_imports(ui.input().time);
+        ui.horizontal(|ui| {
+            let filter =
+                TextEdit::singleline(&mut self.bookmark_filter).hint_text(t("filter_bookmarks"));
+            ui.add(filter);
+            ComboBox::from_label(t("sort_by"))
+                .selected_text(self.config.bookmarks_sort.name())
+                .show_ui(ui, |ui| {
+                    for sort in BookmarkSort::ALL {
+                        ui.selectable_value(&mut self.config.bookmarks_sort, sort, sort.name());
+                    }
+                });
+            if ui.button(t("export")).clicked() {
+                self.export_bookmarks();
+            }
+            if ui.button(t("import")).clicked() {
+                self.import_bookmarks();
+            }
+        });
+
+        let query = self.bookmark_filter.trim();
+        let mut matches: Vec<&Bookmark> = self
+            .config
+            .bookmarks
+            .iter()
+            .filter(|b| {
+                let description = b.description.as_deref().unwrap_or_default();
+                matches_filter(query, &[&b.title, description])
+            })
+            .collect();
+        self.config.bookmarks_sort.sort(&mut matches);
+        if query.is_empty() {
+            ui.label(tf("bookmark_count", &[&format_count(matches.len() as u64)]));
+        } else {
+            let total = self.config.bookmarks.len();
+            let (shown, total) = (format_count(matches.len() as u64), format_count(total as u64));
+            ui.label(tf("bookmarks_match", &[&shown, &total]));
+        }
+        let max = self.config.max_bookmarks;
+        if max > 0 && self.config.bookmarks.len() as f32 >= max as f32 * BOOKMARKS_WARN_RATIO {
+            let warning = tf("bookmarks_near_limit", &[&self.config.bookmarks.len(), &max]);
+            ui.colored_label(AMBER, warning);
+        }
+        ui.add(Separator::default());
+
+        let mut removed = None;
+        for b in matches {
+            ui.add_space(PADDING);
+            ui.label(RichText::new(format!("{} {}", icon("★"), b.title)).strong());
+            if !b.source.is_empty() {
+                ui.label(RichText::new(&b.source).small().weak());
+            }
+            if let Some(description) = &b.description {
+                ui.label(description);
+            }
+            ui.with_layout(Layout::right_to_left().with_cross_align(Align::Min), |ui| {
+                if is_web_url(&b.url) {
+                    ui.add(Hyperlink::from_label_and_url(plain(t("read_more")), &b.url));
+                }
+                if ui.add(Button::new(t("remove")).small()).clicked() {
+                    removed = Some(b.id.clone());
+                }
+            });
+            ui.add_space(PADDING);
+            ui.add(Separator::default());
+        }
+        if let Some(id) = removed {
+            self.config.bookmarks.retain(|b| b.id != id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bookmark(id: &str, added_at: Option<f64>) -> Bookmark {
+        let card = NewsCardData::new(id, &format!("https://example.com/{}", id));
+        Bookmark {
+            added_at,
+            ..Bookmark::from(&card)
+        }
+    }
+
+    fn titles(headlines: &Headlines) -> Vec<&str> {
+        headlines.config.bookmarks.iter().map(|b| b.title.as_str()).collect()
+    }
+
+    #[test]
+    fn the_oldest_bookmarks_are_evicted_first() {
+        let mut headlines = Headlines::new();
+        headlines.config.max_bookmarks = 2;
+        headlines.config.bookmarks = vec![
+            bookmark("newest", Some(30.)),
+            bookmark("oldest", Some(10.)),
+            bookmark("legacy", None),
+            bookmark("middle", Some(20.)),
+        ];
+        headlines.evict_bookmarks();
+        assert_eq!(titles(&headlines), ["newest", "middle"]);
+    }
+
+    #[test]
+    fn nothing_is_evicted_within_the_cap_or_without_one() {
+        let mut headlines = Headlines::new();
+        headlines.config.max_bookmarks = 2;
+        headlines.config.bookmarks = vec![bookmark("a", Some(1.)), bookmark("b", Some(2.))];
+        headlines.evict_bookmarks();
+        assert_eq!(titles(&headlines), ["a", "b"]);
+        headlines.config.max_bookmarks = 0;
+        headlines.config.bookmarks.push(bookmark("c", None));
+        headlines.evict_bookmarks();
+        assert_eq!(titles(&headlines), ["a", "b", "c"]);
+    }
+}
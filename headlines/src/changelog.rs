@@ -0,0 +1,40 @@
+use eframe::egui::{Context, Id, RichText, ScrollArea, Window};
+
+use crate::{headlines::Headlines, i18n::t};
+
+const CHANGELOG: &str = include_str!("../CHANGELOG.md");
+
+impl Headlines {
+    /// Shown once after each update, until the user closes it.
+    pub fn render_changelog(&mut self, ctx: &Context) {
+        let version = env!("CARGO_PKG_VERSION");
+        if self.config.last_seen_version == version {
+            return;
+        }
+        let mut open = true;
+        Window::new(t("whats_new"))
+            .id(Id::new("changelog"))
+            .open(&mut open)
+            .collapsible(false)
+            .default_width(420.0)
+            .show(ctx, |ui| {
+                ScrollArea::vertical().max_height(360.0).show(ui, |ui| {
+                    // a tiny subset of markdown, enough for the bundled file
+                    for line in CHANGELOG.lines().skip_while(|l| l.starts_with("# ")) {
+                        if let Some(heading) = line.strip_prefix("## ") {
+                            ui.add_space(4.0);
+                            ui.label(RichText::new(heading).strong());
+                        } else if let Some(item) = line.strip_prefix("- ") {
+                            ui.label(format!("• {}", item));
+                        }
+                    }
+                });
+                if ui.button(t("got_it")).clicked() {
+                    self.config.last_seen_version = version.to_string();
+                }
+            });
+        if !open {
+            self.config.last_seen_version = version.to_string();
+        }
+    }
+}
@@ -0,0 +1,173 @@
+use serde::{Deserialize, Serialize};
+
+use crate::NewsCardData;
+
+const MONTHS: [&str; 12] = [
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+
+/// MLA abbreviates months longer than four letters, and not always to three of them.
+const MLA_MONTHS: [&str; 12] = [
+    "Jan.", "Feb.", "Mar.", "Apr.", "May", "June", "July", "Aug.", "Sept.", "Oct.", "Nov.", "Dec.",
+];
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CitationStyle {
+    #[default]
+    Apa,
+    Mla,
+}
+
+impl CitationStyle {
+    pub const ALL: [CitationStyle; 2] = [CitationStyle::Apa, CitationStyle::Mla];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            CitationStyle::Apa => "APA",
+            CitationStyle::Mla => "MLA",
+        }
+    }
+
+    pub fn cite(&self, a: &NewsCardData) -> String {
+        let date = a.published_at.as_deref().and_then(parse_date);
+        let author = a.author.as_deref().filter(|s| !s.trim().is_empty());
+        match self {
+            CitationStyle::Apa => apa(author, &a.title, date, &a.source, &a.url),
+            CitationStyle::Mla => mla(author, &a.title, date, &a.source, &a.url),
+        }
+    }
+}
+
+/// Year, month and day of an ISO 8601 timestamp like `2022-05-10T12:00:00Z`.
+fn parse_date(s: &str) -> Option<(u32, usize, u32)> {
+    let mut parts = s.get(..10)?.split('-');
+    let year = parts.next()?.parse().ok()?;
+    let month: usize = parts.next()?.parse().ok()?;
+    let day = parts.next()?.parse().ok()?;
+    (1..=12).contains(&month).then_some((year, month, day))
+}
+
+/// `Author. (2022, May 10). Title. Source. URL`; the title takes the author's
+/// place when there is none and a missing date becomes `(n.d.)`.
+pub fn apa(
+    author: Option<&str>,
+    title: &str,
+    date: Option<(u32, usize, u32)>,
+    source: &str,
+    url: &str,
+) -> String {
+    let date = match date {
+        Some((y, m, d)) => format!("({}, {} {})", y, MONTHS[m - 1], d),
+        None => "(n.d.)".to_string(),
+    };
+    let mut parts = match author {
+        Some(author) => vec![with_period(author), format!("{}.", date), with_period(title)],
+        None => vec![with_period(title), format!("{}.", date)],
+    };
+    if !source.is_empty() {
+        parts.push(with_period(source));
+    }
+    parts.push(url.to_string());
+    parts.join(" ")
+}
+
+/// `Author. "Title." Source, 10 Sept. 2022, URL.`; elements that are missing are
+/// left out along with their punctuation.
+pub fn mla(
+    author: Option<&str>,
+    title: &str,
+    date: Option<(u32, usize, u32)>,
+    source: &str,
+    url: &str,
+) -> String {
+    let mut out = String::new();
+    if let Some(author) = author {
+        out.push_str(&with_period(author));
+        out.push(' ');
+    }
+    out.push_str(&format!("\"{}.\"", title.trim_end_matches('.')));
+    let mut container = Vec::new();
+    if !source.is_empty() {
+        container.push(source.to_string());
+    }
+    if let Some((y, m, d)) = date {
+        container.push(format!("{} {} {}", d, MLA_MONTHS[m - 1], y));
+    }
+    container.push(url.to_string());
+    out.push(' ');
+    out.push_str(&container.join(", "));
+    out.push('.');
+    out
+}
+
+fn with_period(s: &str) -> String {
+    let s = s.trim();
+    if s.ends_with('.') || s.ends_with('?') || s.ends_with('!') {
+        s.to_string()
+    } else {
+        format!("{}.", s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const URL: &str = "https://example.com/story";
+
+    #[test]
+    fn apa_cites_author_date_and_source() {
+        let cited = apa(Some("Jane Doe"), "A Story", Some((2022, 5, 10)), "The Times", URL);
+        let expected = "Jane Doe. (2022, May 10). A Story. The Times. https://example.com/story";
+        assert_eq!(cited, expected);
+    }
+
+    #[test]
+    fn apa_puts_the_title_first_without_an_author() {
+        let cited = apa(None, "A Story?", None, "", URL);
+        assert_eq!(cited, "A Story? (n.d.). https://example.com/story");
+    }
+
+    #[test]
+    fn mla_cites_author_title_and_container() {
+        let cited = mla(Some("Jane Doe"), "A Story.", Some((2022, 9, 3)), "The Times", URL);
+        let expected = "Jane Doe. \"A Story.\" The Times, 3 Sept. 2022, https://example.com/story.";
+        assert_eq!(cited, expected);
+    }
+
+    #[test]
+    fn mla_leaves_out_what_is_missing() {
+        assert_eq!(mla(None, "A Story", None, "", URL), "\"A Story.\" https://example.com/story.");
+    }
+
+    #[test]
+    fn mla_abbreviates_months_its_own_way() {
+        for (m, month) in [(1, "Jan."), (5, "May"), (6, "June"), (7, "July"), (9, "Sept.")] {
+            let cited = mla(None, "Title", Some((2022, m, 1)), "", URL);
+            assert!(cited.contains(&format!(" 1 {} 2022,", month)), "{}", cited);
+        }
+    }
+
+    #[test]
+    fn cite_reads_the_date_and_skips_a_blank_author() {
+        let mut a = NewsCardData::new("A Story", URL);
+        a.source = "The Times".to_string();
+        a.author = Some("  ".to_string());
+        a.published_at = Some("2022-02-01T08:00:00Z".to_string());
+        let cited = CitationStyle::Apa.cite(&a);
+        assert_eq!(cited, "A Story. (2022, February 1). The Times. https://example.com/story");
+        let cited = CitationStyle::Mla.cite(&a);
+        assert_eq!(cited, "\"A Story.\" The Times, 1 Feb. 2022, https://example.com/story.");
+    }
+}
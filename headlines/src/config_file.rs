@@ -0,0 +1,161 @@
+use std::{
+    env, fs, io,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use eframe::{egui::Vec2, NativeOptions};
+use newsapi::Category;
+use serde::{Deserialize, Serialize};
+
+use crate::headlines::HeadlinesConfig;
+
+pub const API_KEY_ENV: &str = "NEWSAPI_KEY";
+/// What the app is run as, eframe names its storage directory after it.
+pub const APP_NAME: &str = "Headlines";
+
+/// Settings read from `~/.config/headlines/config.toml`, e.g.
+///
+/// ```toml
+/// api_key = "..."
+/// category = "technology"
+/// ```
+#[derive(Deserialize, Default)]
+pub struct FileConfig {
+    pub api_key: Option<String>,
+    pub category: Option<Category>,
+    pub dark_mode: Option<bool>,
+}
+
+fn config_dir() -> Option<PathBuf> {
+    let config_dir = env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    Some(config_dir.join("headlines"))
+}
+
+/// The directory eframe keeps its `app.ron` in, found the same way eframe finds it, e.g.
+/// `~/.local/share/Headlines` on Linux.
+pub fn storage_dir() -> Option<PathBuf> {
+    let dirs = directories_next::ProjectDirs::from("", "", APP_NAME)?;
+    Some(dirs.data_dir().to_path_buf())
+}
+
+/// Opens `dir` in the platform's file manager.
+pub fn reveal(dir: &Path) -> io::Result<()> {
+    let program = if cfg!(target_os = "windows") {
+        "explorer"
+    } else if cfg!(target_os = "macos") {
+        "open"
+    } else {
+        "xdg-open"
+    };
+    Command::new(program).arg(dir).spawn().map(drop)
+}
+
+impl FileConfig {
+    pub fn path() -> Option<PathBuf> {
+        Some(config_dir()?.join("config.toml"))
+    }
+
+    pub fn load() -> Option<FileConfig> {
+        let path = Self::path()?;
+        let contents = fs::read_to_string(&path).ok()?;
+        match toml::from_str(&contents) {
+            Ok(config) => Some(config),
+            Err(e) => {
+                tracing::error!("failed parsing {}: {}", path.display(), e);
+                None
+            }
+        }
+    }
+
+    pub fn apply(self, config: &mut HeadlinesConfig) {
+        if let Some(api_key) = self.api_key.filter(|k| !k.is_empty()) {
+            config.api_key = api_key;
+        }
+        if let Some(category) = self.category {
+            config.category = category;
+        }
+        if let Some(dark_mode) = self.dark_mode {
+            config.dark_mode = dark_mode;
+        }
+    }
+}
+
+/// Applies the config file and then the environment on top of the stored config,
+/// giving the precedence env > file > stored. Returns the api key when one of them
+/// set it, so it can be kept out of eframe's storage.
+pub fn apply_overrides(config: &mut HeadlinesConfig) -> Option<String> {
+    let mut api_key = None;
+    if let Some(file) = FileConfig::load() {
+        api_key = file.api_key.clone().filter(|k| !k.is_empty());
+        file.apply(config);
+    }
+    if let Ok(key) = env::var(API_KEY_ENV) {
+        if !key.is_empty() {
+            config.api_key = key.clone();
+            api_key = Some(key);
+        }
+    }
+    api_key
+}
+
+pub const WINDOW_SIZE: Vec2 = Vec2::new(640.0, 860.0);
+pub const MIN_WINDOW_SIZE: Vec2 = Vec2::new(360.0, 480.0);
+pub const TICKER_WINDOW_SIZE: Vec2 = Vec2::new(640.0, 40.0);
+
+/// Window options that have to be known before the window is created, so they
+/// live in `~/.config/headlines/window.toml` rather than in eframe's storage.
+#[derive(Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WindowOptions {
+    pub always_on_top: bool,
+    pub ticker: bool,
+}
+
+impl WindowOptions {
+    fn path() -> Option<PathBuf> {
+        Some(config_dir()?.join("window.toml"))
+    }
+
+    pub fn load() -> WindowOptions {
+        Self::path()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|contents| toml::from_str(&contents).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) {
+        let path = match Self::path() {
+            Some(path) => path,
+            None => return,
+        };
+        let contents = match toml::to_string(self) {
+            Ok(contents) => contents,
+            Err(e) => return tracing::error!("failed serializing window options: {}", e),
+        };
+        if let Some(dir) = path.parent() {
+            if let Err(e) = fs::create_dir_all(dir) {
+                return tracing::error!("failed creating {}: {}", dir.display(), e);
+            }
+        }
+        if let Err(e) = fs::write(&path, contents) {
+            tracing::error!("failed writing {}: {}", path.display(), e);
+        }
+    }
+
+    pub fn native_options(&self) -> NativeOptions {
+        let (initial_window_size, min_window_size) = if self.ticker {
+            (TICKER_WINDOW_SIZE, None)
+        } else {
+            (WINDOW_SIZE, Some(MIN_WINDOW_SIZE))
+        };
+        NativeOptions {
+            always_on_top: self.always_on_top,
+            initial_window_size: Some(initial_window_size),
+            min_window_size,
+            ..Default::default()
+        }
+    }
+}
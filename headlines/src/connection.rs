@@ -0,0 +1,92 @@
+use std::sync::mpsc::{channel, Receiver};
+
+use eframe::egui::{Button, Color32, Spinner, Ui};
+use newsapi::{NewsAPI, NewsAPIResponse, NewsApiError};
+
+use crate::{
+    headlines::{Headlines, RED},
+    i18n::{t, tf},
+    source::newsapi_client,
+};
+
+const GREEN: Color32 = Color32::from_rgb(0, 190, 90);
+
+/// A one-off check that newsapi can be reached and takes the key, separate from the feed.
+#[derive(Default)]
+pub struct ConnectionTest {
+    rx: Option<Receiver<Result<NewsAPIResponse, NewsApiError>>>,
+    /// What to show for the last finished check, and whether it passed.
+    outcome: Option<(String, bool)>,
+}
+
+impl ConnectionTest {
+    #[cfg(not(target_arch = "wasm32"))]
+    fn start(&mut self, api: NewsAPI) {
+        let (tx, rx) = channel();
+        self.rx = Some(rx);
+        std::thread::spawn(move || {
+            let _ = tx.send(api.fetch());
+        });
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn start(&mut self, api: NewsAPI) {
+        let (tx, rx) = channel();
+        self.rx = Some(rx);
+        wasm_bindgen_futures::spawn_local(async move {
+            let _ = tx.send(api.fetch_web().await);
+        });
+    }
+
+    fn poll(&mut self) {
+        let result = match self.rx.as_ref().and_then(|rx| rx.try_recv().ok()) {
+            Some(result) => result,
+            None => return,
+        };
+        self.rx = None;
+        self.outcome = Some(match result {
+            Ok(_) => (t("connection_ok").to_string(), true),
+            Err(e) => (describe(&e), false),
+        });
+    }
+}
+
+/// Names the failure in plain words, with the HTTP status when there was one.
+fn describe(e: &NewsApiError) -> String {
+    let what = match e {
+        NewsApiError::BadApiKey => t("connection_bad_key").to_string(),
+        NewsApiError::RateLimited => t("connection_rate_limited").to_string(),
+        e if e.is_network_error() => t("connection_no_network").to_string(),
+        e => tf("connection_failed", &[e]),
+    };
+    match e.status() {
+        Some(status) => tf("connection_status", &[&what, &status]),
+        None => what,
+    }
+}
+
+impl Headlines {
+    /// A "Test connection" button that fetches a single headline with the entered key
+    /// and reports how that went.
+    pub fn render_connection_test(&mut self, ui: &mut Ui) {
+        let test = &mut self.connection_test;
+        test.poll();
+        ui.horizontal(|ui| {
+            let running = test.rx.is_some();
+            if ui.add_enabled(!running, Button::new(t("test_connection"))).clicked() {
+                let mut api = newsapi_client(self.config.api_key.trim());
+                api.page_size(1);
+                #[cfg(not(target_arch = "wasm32"))]
+                api.danger_accept_invalid_certs(self.config.danger_accept_invalid_certs);
+                test.outcome = None;
+                test.start(api);
+            }
+            if running {
+                ui.add(Spinner::new());
+                ui.ctx().request_repaint();
+            } else if let Some((outcome, ok)) = &test.outcome {
+                ui.colored_label(if *ok { GREEN } else { RED }, outcome);
+            }
+        });
+    }
+}
@@ -0,0 +1,69 @@
+use std::any::Any;
+
+use eframe::egui::{CentralPanel, Context, RichText};
+
+use crate::{headlines::Headlines, i18n::t};
+
+/// The message `panic!` was given, when it was a string.
+pub(crate) fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        t("crash_unknown").to_string()
+    }
+}
+
+impl Headlines {
+    pub(crate) fn render_crash(&mut self, ctx: &Context) {
+        let message = self.crash.clone().unwrap_or_default();
+        CentralPanel::default().show(ctx, |ui| {
+            ui.vertical_centered(|ui| {
+                ui.heading(t("crash_heading"));
+                ui.label(t("crash_explanation"));
+                ui.label(RichText::new(message).monospace());
+                if ui.button(t("crash_reload")).clicked() {
+                    // the config survived, so drawing again usually just works
+                    self.crash = None;
+                }
+            });
+        });
+    }
+}
+
+/// Wasm builds abort on panic, so `update` can't catch anything there. Instead the page
+/// is swapped for the message and a reload button just before the abort.
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn install_panic_page() {
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        default_hook(info);
+        show_panic_page(&panic_message(info.payload()));
+    }));
+}
+
+#[cfg(target_arch = "wasm32")]
+fn show_panic_page(message: &str) {
+    let document = match web_sys::window().and_then(|w| w.document()) {
+        Some(document) => document,
+        None => return,
+    };
+    let body = match document.body() {
+        Some(body) => body,
+        None => return,
+    };
+    body.set_inner_html("");
+    let add = |tag: &str, text: &str| {
+        let element = document.create_element(tag).ok()?;
+        element.set_text_content(Some(text));
+        body.append_child(&element).ok()?;
+        Some(element)
+    };
+    add("h2", t("crash_heading"));
+    add("p", t("crash_explanation"));
+    add("pre", message);
+    if let Some(button) = add("button", t("crash_reload")) {
+        let _ = button.set_attribute("onclick", "location.reload()");
+    }
+}
@@ -0,0 +1,106 @@
+use miniz_oxide::{deflate::compress_to_vec, inflate::decompress_to_vec};
+
+/// Articles deflated together, enough for the text to compress well while expanding a
+/// card only has a batch to inflate.
+const BATCH_SIZE: usize = 25;
+const COMPRESSION_LEVEL: u8 = 6;
+
+/// An article's description and content, set aside while its card is folded.
+type Texts = (Option<String>, Option<String>);
+
+/// The descriptions and contents compact cards don't show, kept deflated in batches
+/// instead of as strings on every card, and handed back when a card is expanded.
+#[derive(Default)]
+pub struct DeferredText {
+    /// Articles waiting for their batch to fill up.
+    pending: Vec<(String, Texts)>,
+    /// Each a deflated JSON list of `(id, texts)`.
+    batches: Vec<Vec<u8>>,
+}
+
+impl DeferredText {
+    pub fn defer(&mut self, id: &str, texts: Texts) {
+        if texts == (None, None) {
+            return;
+        }
+        self.pending.push((id.to_string(), texts));
+        if self.pending.len() >= BATCH_SIZE {
+            self.flush();
+        }
+    }
+
+    /// Deflates whatever is pending, called once a fetch is done so a short last batch
+    /// doesn't stay uncompressed.
+    pub fn flush(&mut self) {
+        if self.pending.is_empty() {
+            return;
+        }
+        match serde_json::to_vec(&self.pending) {
+            Ok(json) => {
+                self.batches.push(compress_to_vec(&json, COMPRESSION_LEVEL));
+                self.pending.clear();
+            }
+            Err(e) => tracing::warn!("failed packing deferred descriptions: {}", e),
+        }
+    }
+
+    /// The texts deferred for `id`. A batch isn't rewritten to drop them, so they stay
+    /// there until the feed is cleared.
+    pub fn take(&mut self, id: &str) -> Option<Texts> {
+        if let Some(i) = self.pending.iter().position(|(pending, _)| pending == id) {
+            return Some(self.pending.swap_remove(i).1);
+        }
+        self.batches.iter().find_map(|batch| {
+            let json = decompress_to_vec(batch).ok()?;
+            let articles: Vec<(String, Texts)> = serde_json::from_slice(&json).ok()?;
+            articles.into_iter().find(|(deferred, _)| deferred == id).map(|(_, texts)| texts)
+        })
+    }
+
+    pub fn clear(&mut self) {
+        self.pending.clear();
+        self.batches.clear();
+    }
+
+    /// Bytes the deferred text takes up, for the diagnostics.
+    pub fn size(&self) -> usize {
+        let pending: usize = self
+            .pending
+            .iter()
+            .map(|(id, (description, content))| {
+                let len = |s: &Option<String>| s.as_ref().map_or(0, String::len);
+                id.len() + len(description) + len(content)
+            })
+            .sum();
+        pending + self.batches.iter().map(Vec::len).sum::<usize>()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn texts(i: usize) -> Texts {
+        (Some(format!("description {}", i)), Some(format!("content {}", i)))
+    }
+
+    #[test]
+    fn take_finds_pending_and_deflated_text() {
+        let mut deferred = DeferredText::default();
+        for i in 0..BATCH_SIZE + 3 {
+            deferred.defer(&i.to_string(), texts(i));
+        }
+        assert_eq!(deferred.batches.len(), 1);
+        assert_eq!(deferred.take("2"), Some(texts(2)));
+        assert_eq!(deferred.take(&(BATCH_SIZE + 1).to_string()), Some(texts(BATCH_SIZE + 1)));
+        assert_eq!(deferred.take("missing"), None);
+    }
+
+    #[test]
+    fn nothing_to_defer_is_skipped() {
+        let mut deferred = DeferredText::default();
+        deferred.defer("id", (None, None));
+        deferred.flush();
+        assert_eq!(deferred.size(), 0);
+    }
+}
@@ -0,0 +1,88 @@
+use std::collections::BTreeMap;
+
+use eframe::egui::{Button, CollapsingHeader, Hyperlink, Label, RichText, Separator, Ui};
+
+use crate::{
+    headlines::Headlines,
+    i18n::{t, tf},
+    NewsCardData, PADDING,
+};
+
+/// How many articles each source shows before the rest go under its expander.
+const DIGEST_TOP: usize = 3;
+
+/// The articles published on the newest day in the feed, grouped by source with
+/// the busiest sources first. Articles keep their feed order inside a group.
+fn group_by_source(articles: &[NewsCardData]) -> Vec<(&str, Vec<&NewsCardData>)> {
+    let latest = articles.iter().filter_map(published_day).max();
+
+    let mut groups: BTreeMap<&str, Vec<&NewsCardData>> = BTreeMap::new();
+    for a in articles.iter().filter(|a| latest.is_none() || published_day(a) == latest) {
+        groups.entry(a.source.as_str()).or_default().push(a);
+    }
+    let mut groups: Vec<_> = groups.into_iter().collect();
+    groups.sort_by_key(|(_, articles)| std::cmp::Reverse(articles.len()));
+    groups
+}
+
+/// The `YYYY-MM-DD` part of an RFC 3339 timestamp.
+fn published_day(a: &NewsCardData) -> Option<&str> {
+    a.published_at.as_deref().and_then(|p| p.get(..10))
+}
+
+/// Returns whether the article's link was clicked.
+fn render_digest_row(ui: &mut Ui, a: &NewsCardData, read: bool) -> bool {
+    let title = if read { RichText::new(&a.title).weak() } else { RichText::new(&a.title) };
+    if a.url_valid {
+        ui.add(Hyperlink::from_label_and_url(title, &a.url)).clicked()
+    } else {
+        ui.add(Label::new(title).wrap(true));
+        false
+    }
+}
+
+impl Headlines {
+    pub fn render_digest(&mut self, ui: &mut Ui) {
+        let mut opened = None;
+        let mut marked = None;
+        for (source, articles) in group_by_source(&self.articles) {
+            ui.add_space(PADDING);
+            let source = if source.is_empty() { t("unknown_source") } else { source };
+            let all_read = articles.iter().all(|a| self.is_read(a.id()));
+            ui.horizontal(|ui| {
+                let heading = RichText::new(format!("{} ({})", source, articles.len())).strong();
+                ui.label(if all_read { heading.weak() } else { heading });
+                let mark = ui.add_enabled(!all_read, Button::new(t("mark_all_read")).small());
+                if mark.clicked() {
+                    marked = Some(articles.iter().map(|a| a.id().to_string()).collect::<Vec<_>>());
+                }
+            });
+            let mut render_row = |ui: &mut Ui, a: &NewsCardData| {
+                if render_digest_row(ui, a, self.is_read(a.id())) {
+                    opened = Some(a.id().to_string());
+                }
+            };
+            for a in articles.iter().take(DIGEST_TOP) {
+                render_row(ui, a);
+            }
+            if articles.len() > DIGEST_TOP {
+                CollapsingHeader::new(tf("show_all_from", &[&source]))
+                    .id_source(("digest", source))
+                    .show(ui, |ui| {
+                        for a in &articles[DIGEST_TOP..] {
+                            render_row(ui, a);
+                        }
+                    });
+            }
+            ui.add_space(PADDING);
+            ui.add(Separator::default());
+        }
+        if let Some(id) = opened {
+            self.config.stats.article_opened();
+            self.mark_read([id]);
+        }
+        if let Some(ids) = marked {
+            self.mark_read(ids);
+        }
+    }
+}
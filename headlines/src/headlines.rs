@@ -1,35 +1,250 @@
 use std::{
-    sync::mpsc::{channel, sync_channel, Receiver, Sender, SyncSender},
+    collections::{HashMap, HashSet, VecDeque},
+    ops::Range,
+    sync::mpsc::{sync_channel, Receiver, SyncSender, TrySendError},
 };
 
 #[cfg(not(target_arch = "wasm32"))]
-use std::thread;
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    thread,
+};
+#[cfg(target_arch = "wasm32")]
+use std::{cell::Cell, cell::RefCell, rc::Rc};
 
 use eframe::{
     egui::{
-        menu, Button, CentralPanel, Color32, Context, FontData, FontDefinitions, FontFamily,
-        Hyperlink, Key, Label, Layout, RichText, Separator, TextStyle, TopBottomPanel, Ui, Window,
+        menu, Align2, Area, Button, CentralPanel, Color32, ComboBox, Context, DragValue, Event,
+        FontData, FontDefinitions, FontFamily, Frame, Hyperlink, Id, ImageButton, Key, Label,
+        Layout, Rect, Response, RichText, Sense, Separator, Spinner, Stroke, TextEdit, TextStyle,
+        TopBottomPanel, Ui, Vec2, WidgetInfo, WidgetText, WidgetType, Window,
     },
     CreationContext, emath::Align,
 };
-use newsapi::NewsAPI;
+use eframe::egui::text::{LayoutJob, TextFormat};
+use chrono::{DateTime, Duration, SecondsFormat, Utc};
+use newsapi::{Article, Category, Country, NewsApiError};
 use serde::{Deserialize, Serialize};
 
+#[cfg(not(target_arch = "wasm32"))]
+use crate::config_file::{reveal, storage_dir, WindowOptions, TICKER_WINDOW_SIZE, WINDOW_SIZE};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::notify::HeadlineNotifier;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::reader::{Extractor, ReaderPane};
+use crate::bookmark_io::BookmarkFiles;
+use crate::blend::{blend_queries, merge_blend};
+use crate::deferred::DeferredText;
+use crate::line_style::LineStyle;
+use crate::bookmarks::{Bookmark, BookmarkSort, MAX_BOOKMARKS};
+use crate::health::SourceHealth;
+use crate::source_filter::{normalize_filter, SourceList};
+use crate::numbers::{format_count, NumberFormat};
+use crate::paging::Paging;
+use crate::pull::PULL_REFRESH_DISTANCE;
+use crate::split::FeedPane;
+use crate::stats::ReadingStats;
+use crate::citation::CitationStyle;
+use crate::connection::ConnectionTest;
+use crate::i18n::{t, tf, Language};
+use crate::locale::detect_country;
+use crate::icons::{self, icon, plain, IconSet};
+use crate::images::{ImageKey, ImageLoader, ImageState, MAX_IMAGE_DOWNLOADS};
+use crate::searches::SavedSearch;
+use crate::source::{
+    ArticleSource, BoxedSource, Fetched, HomeFeed, Query, SearchScope, SourceKind,
+};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::source::http_agent;
+use crate::text::{
+    article_id, decode_entities, is_web_url, normalize_title, similar_titles,
+    split_content_marker, strip_source_suffix, truncate_words, watch_matches, watch_terms,
+};
+use crate::post_process::{NoPostProcess, PostProcess};
+use crate::translate::{NoopTranslator, Translator};
+use crate::wake::Waker;
+
 pub const PADDING: f32 = 5.0;
+/// Key the config is kept under in eframe's storage.
+pub const STORAGE_KEY: &str = "headlines";
+/// Names a profile kept apart from the default config, e.g. `HEADLINES_PROFILE=work`.
+#[cfg(not(target_arch = "wasm32"))]
+pub const PROFILE_ENV: &str = "HEADLINES_PROFILE";
+const SEARCH_BOX_ID: &str = "search_box";
+/// Seconds the "NEW" badge stays visible before it has fully faded out.
+const NEW_BADGE_SECS: f64 = 5.0;
+/// Height of the thumbnail shown on each card.
+const THUMBNAIL_HEIGHT: f32 = 120.0;
+/// Seconds a toast message stays on screen.
+const TOAST_SECS: f64 = 3.0;
+/// Fraction of the auto-refresh interval randomly added or taken off each time,
+/// so many running copies of the app don't all hit newsapi at once.
+const REFRESH_JITTER: f64 = 0.1;
+/// Default number of fetch events buffered between the worker and the UI.
+const NEWS_CHANNEL_CAPACITY: usize = 32;
+/// How long the worker waits on a full news channel before dropping an article.
+const SEND_TIMEOUT_MS: u32 = 2000;
+/// Pause between attempts to send on a full news channel.
+const SEND_RETRY_MS: u32 = 10;
+/// Default milliseconds between the first frame and the initial fetch on wasm.
+const STARTUP_FETCH_DELAY_MS: u32 = 10;
+/// Read article ids remembered, enough to cover many refreshes of every category.
+const READ_HISTORY: usize = 5000;
+/// /everything needs some query, this matches any article.
+const DEFAULT_HOME_QUERY: &str = "*";
+/// Points per second the ticker text scrolls by.
+#[cfg(not(target_arch = "wasm32"))]
+const TICKER_SPEED: f32 = 60.0;
 const WHITE: Color32 = Color32::from_rgb(255, 255, 255);
 const BLACK: Color32 = Color32::from_rgb(0, 0, 0);
 const CYAN: Color32 = Color32::from_rgb(0, 255, 255);
-const RED: Color32 = Color32::from_rgb(255, 0, 0);
+pub(crate) const AMBER: Color32 = Color32::from_rgb(220, 160, 0);
+pub(crate) const RED: Color32 = Color32::from_rgb(255, 0, 0);
+/// Accent used until the user picks their own in settings.
+const DEFAULT_ACCENT: Color32 = Color32::from_rgb(0, 140, 190);
 
 pub enum Msg {
     ApiKeySet(String),
     Refresh,
+    CategoryChanged(Category),
+    SourceChanged(SourceKind),
+    RssUrlChanged(String),
+    HomeFeedChanged(Option<String>),
+    CountryChanged(Country),
+    /// Switches to [`LOW_BANDWIDTH_PAGE_SIZE`] pages from the next fetch on, or back.
+    LowBandwidth(bool),
+    /// Categories merged into the top headlines alongside the selected one.
+    BlendChanged(Vec<Category>),
+    SourceFilterChanged(Option<String>),
+    #[cfg(not(target_arch = "wasm32"))]
+    AcceptInvalidCerts(bool),
+    /// Fetches page `n` of the current query ahead of time.
+    FetchPage(u32),
+    /// Starts over with the query and source of the default settings. Nothing is fetched
+    /// until the key window sends `ApiKeySet`.
+    Reset {
+        query: Box<Query>,
+        source: SourceKind,
+    },
+    SearchChanged {
+        search: Option<String>,
+        scope: SearchScope,
+        category: Category,
+    },
+}
+
+#[derive(Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum StartupView {
+    #[default]
+    LastCategory,
+    Category(Category),
+    /// Whatever was being searched for on exit, the last category when nothing was.
+    LastSearch,
 }
 
 #[derive(Serialize, Deserialize)]
+#[serde(default)]
 pub struct HeadlinesConfig {
     pub dark_mode: bool,
     pub api_key: String,
+    pub translate_titles: bool,
+    pub compact: bool,
+    pub lazy_descriptions: bool,
+    pub category: Category,
+    pub notify_new_headlines: bool,
+    pub startup_view: StartupView,
+    pub description_max_chars: usize,
+    /// Seconds since the last successful fetch before a refresh hits the API again.
+    pub min_refresh_interval: f64,
+    pub bookmarks: Vec<Bookmark>,
+    pub bookmarks_sort: BookmarkSort,
+    /// Tints the header, the active category tab and selection highlights.
+    pub accent_color: Color32,
+    pub source: SourceKind,
+    /// Address of the feed the RSS source reads.
+    pub rss_url: String,
+    pub citation_style: CitationStyle,
+    /// Shows raw article fields on hover and egui's own widget debugging.
+    pub debug_overlay: bool,
+    /// Milliseconds the first wasm fetch waits after the first frame is drawn.
+    pub startup_fetch_delay_ms: u32,
+    /// Fetch events buffered for the UI, takes effect on the next launch.
+    pub news_channel_capacity: usize,
+    pub search_scope: SearchScope,
+    pub language: Language,
+    /// Ids of articles kept at the top of the feed until unpinned.
+    pub pinned: Vec<String>,
+    pub density: Density,
+    /// Minutes between automatic refreshes, 0 to only refresh by hand.
+    pub auto_refresh_mins: u32,
+    pub saved_searches: Vec<SavedSearch>,
+    /// Native only, skips TLS certificate checks for proxies that re-sign traffic.
+    pub danger_accept_invalid_certs: bool,
+    pub stats: ReadingStats,
+    pub home_feed: HomeFeed,
+    /// Keywords for the /everything home feed.
+    pub home_query: String,
+    /// Hides articles whose title matches one already in the feed, keeping the first.
+    pub hide_duplicates: bool,
+    /// Oldest-added bookmarks are dropped past this many, 0 for no limit.
+    pub max_bookmarks: usize,
+    /// Stops drawing frames while nothing changes, instead of redrawing continuously.
+    pub pause_when_idle: bool,
+    /// Ids of articles opened or marked as read, oldest first.
+    pub read: Vec<String>,
+    /// Ids of articles the new-headline notifications already counted, oldest first.
+    pub seen: Vec<String>,
+    /// Ids of each category's latest top headlines, for the unread badges on the tabs.
+    pub category_ids: Vec<(Category, Vec<String>)>,
+    pub click_action: ClickAction,
+    /// Comma separated newsapi source ids the feed is limited to, empty for all.
+    pub source_filter: String,
+    /// Most articles in a row from one source before others are pulled forward, 0 for no cap.
+    pub max_run_per_source: usize,
+    /// Version whose release notes were last shown.
+    pub last_seen_version: String,
+    /// Shows a second, independent feed beside the main one.
+    pub split_view: bool,
+    pub split_category: Category,
+    pub icon_set: IconSet,
+    /// Drops a trailing " - {source}" from titles, the source is shown beside them anyway.
+    pub strip_source_suffix: bool,
+    pub max_image_downloads: usize,
+    /// Folds cards that have been read down to a dimmed title.
+    pub collapse_read: bool,
+    pub time_format: TimeFormat,
+    /// Articles queued to read later, oldest first; opened ones leave the queue.
+    pub read_later: Vec<Bookmark>,
+    /// Whose top headlines to show, `None` until it's been guessed from the locale.
+    pub country: Option<Country>,
+    /// Data saver for metered connections: no thumbnails and smaller pages.
+    pub low_bandwidth: bool,
+    /// Comma separated terms highlighted wherever they show up in a title.
+    pub watchlist: String,
+    /// The oldest articles are trimmed from the feed past this many, 0 for no limit.
+    pub max_feed_length: usize,
+    pub new_articles_at: InsertOrder,
+    /// Points the feed is pulled down past its top to refresh it, 0 turns pulling off.
+    pub pull_refresh_distance: f32,
+    /// Categories whose top headlines are merged into the selected category's.
+    pub blend_categories: Vec<Category>,
+    pub number_format: NumberFormat,
+    /// The search running when the app was closed, for `StartupView::LastSearch`.
+    pub last_search: Option<String>,
+    /// Tags cards with their source's country and language, from newsapi's sources list.
+    pub source_tags: bool,
+    /// Cards published more than this many days ago are hidden, 0 shows every age.
+    pub max_age_days: u32,
+    /// Whether cards without a usable date stay visible while `max_age_days` is on.
+    pub show_undated: bool,
+    /// Hides bookmark, queue, pin and cite until the card is hovered or selected.
+    pub actions_on_hover: bool,
+    /// How folded titles of unread and read cards are drawn.
+    pub unread_line: LineStyle,
+    pub read_line: LineStyle,
 }
 
 impl Default for HeadlinesConfig {
@@ -37,22 +252,406 @@ impl Default for HeadlinesConfig {
         Self {
             dark_mode: Default::default(),
             api_key: String::new(),
+            translate_titles: false,
+            compact: false,
+            lazy_descriptions: false,
+            category: Category::default(),
+            notify_new_headlines: false,
+            startup_view: StartupView::default(),
+            description_max_chars: 200,
+            min_refresh_interval: 60.0,
+            bookmarks: Vec::new(),
+            bookmarks_sort: BookmarkSort::default(),
+            accent_color: DEFAULT_ACCENT,
+            source: SourceKind::default(),
+            rss_url: String::new(),
+            citation_style: CitationStyle::default(),
+            debug_overlay: false,
+            startup_fetch_delay_ms: STARTUP_FETCH_DELAY_MS,
+            news_channel_capacity: NEWS_CHANNEL_CAPACITY,
+            search_scope: SearchScope::default(),
+            language: Language::default(),
+            pinned: Vec::new(),
+            density: Density::default(),
+            auto_refresh_mins: 0,
+            saved_searches: Vec::new(),
+            danger_accept_invalid_certs: false,
+            stats: ReadingStats::default(),
+            home_feed: HomeFeed::default(),
+            home_query: DEFAULT_HOME_QUERY.to_string(),
+            hide_duplicates: false,
+            max_bookmarks: MAX_BOOKMARKS,
+            pause_when_idle: true,
+            read: Vec::new(),
+            seen: Vec::new(),
+            category_ids: Vec::new(),
+            click_action: ClickAction::default(),
+            source_filter: String::new(),
+            max_run_per_source: 0,
+            last_seen_version: String::new(),
+            split_view: false,
+            split_category: Category::Technology,
+            icon_set: IconSet::default(),
+            strip_source_suffix: false,
+            max_image_downloads: MAX_IMAGE_DOWNLOADS,
+            collapse_read: false,
+            time_format: TimeFormat::default(),
+            read_later: Vec::new(),
+            country: None,
+            low_bandwidth: false,
+            watchlist: String::new(),
+            max_feed_length: MAX_FEED_LENGTH,
+            new_articles_at: InsertOrder::default(),
+            pull_refresh_distance: PULL_REFRESH_DISTANCE,
+            blend_categories: Vec::new(),
+            number_format: NumberFormat::default(),
+            last_search: None,
+            source_tags: true,
+            max_age_days: 0,
+            show_undated: true,
+            actions_on_hover: false,
+            unread_line: LineStyle::default(),
+            read_line: LineStyle::default(),
+        }
+    }
+}
+
+/// What clicking a card's title does outside compact mode, where it expands the card.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ClickAction {
+    #[default]
+    Browser,
+    /// The in-app reader, native only; falls back to the browser without an extractor.
+    Reader,
+    MarkRead,
+}
+
+impl ClickAction {
+    pub const ALL: [ClickAction; 3] = [
+        ClickAction::Browser,
+        ClickAction::Reader,
+        ClickAction::MarkRead,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ClickAction::Browser => t("click_browser"),
+            ClickAction::Reader => t("click_reader"),
+            ClickAction::MarkRead => t("click_mark_read"),
+        }
+    }
+}
+
+/// How cards show when an article was published.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TimeFormat {
+    /// "3h ago"
+    #[default]
+    Relative,
+    /// "2024-05-01 14:30 UTC"
+    Absolute,
+}
+
+impl TimeFormat {
+    pub const ALL: [TimeFormat; 2] = [TimeFormat::Relative, TimeFormat::Absolute];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            TimeFormat::Relative => t("time_relative"),
+            TimeFormat::Absolute => t("time_absolute"),
+        }
+    }
+
+    /// `published` in this format, and in the other one for the tooltip.
+    fn both(&self, published: DateTime<Utc>, now: DateTime<Utc>) -> (String, String) {
+        let (relative, absolute) = (relative_time(published, now), absolute_time(published));
+        match self {
+            TimeFormat::Relative => (relative, absolute),
+            TimeFormat::Absolute => (absolute, relative),
+        }
+    }
+}
+
+/// Where a refresh puts articles that weren't in the feed before it.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum InsertOrder {
+    /// Freshest first.
+    #[default]
+    Top,
+    Bottom,
+}
+
+impl InsertOrder {
+    pub const ALL: [InsertOrder; 2] = [InsertOrder::Top, InsertOrder::Bottom];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            InsertOrder::Top => t("new_at_top"),
+            InsertOrder::Bottom => t("new_at_bottom"),
+        }
+    }
+}
+
+/// How tightly cards are packed in the feed.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Density {
+    #[default]
+    Comfortable,
+    /// Thin dividers and almost no padding, for scanning many headlines.
+    Dense,
+}
+
+impl Density {
+    pub const ALL: [Density; 2] = [Density::Comfortable, Density::Dense];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Density::Comfortable => t("density_comfortable"),
+            Density::Dense => t("density_dense"),
         }
     }
+
+    /// Space added around the parts of a card.
+    fn spacing(&self) -> f32 {
+        match self {
+            Density::Comfortable => PADDING,
+            Density::Dense => 1.0,
+        }
+    }
+
+    fn separator_spacing(&self) -> f32 {
+        match self {
+            Density::Comfortable => 6.0,
+            Density::Dense => 2.0,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum View {
+    Feed,
+    Bookmarks,
+    /// The day's articles grouped by source, for breadth over depth.
+    Digest,
+    ReadLater,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum SettingsAction {
+    ResetSettings,
+    ClearCache,
+    #[cfg(not(target_arch = "wasm32"))]
+    AcceptInvalidCerts,
+}
+
+/// What the fetcher sends back. A successful fetch is `Started`, one `Article` per
+/// result and then `Done`, so an empty feed can be told apart from one still loading.
+/// A first page the source says hasn't changed comes as one `NotModified` instead.
+pub enum FetchEvent {
+    Started { total: u64 },
+    Article(Box<NewsCardData>),
+    /// A later page of the current feed, held back until the reader asks for it.
+    NextPage(Vec<NewsCardData>),
+    /// Blended categories that failed while the others loaded, sent after `Started`.
+    CategoriesFailed(Vec<Category>),
+    Done,
+    /// The feed is the same as when the source last sent it. A refresh keeps the articles
+    /// on screen, anything else shows the ones sent along as if they had just arrived.
+    NotModified { total: u64, articles: Vec<NewsCardData> },
+    Error(NewsApiError),
+}
+
+/// Where the fetch behind the current feed is at.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum FetchState {
+    #[default]
+    Loading,
+    Done,
+    Failed(String),
 }
 
 pub struct NewsCardData {
     pub title: String,
-    pub description: String,
+    pub description: Option<String>,
     pub url: String,
+    /// False when `url` isn't an absolute http(s) url, so no link is rendered for it.
+    pub url_valid: bool,
+    id: String,
+    pub source: String,
+    pub source_id: Option<String>,
+    pub author: Option<String>,
+    /// RFC 3339 in UTC, e.g. `2023-05-01T12:34:56Z`.
+    pub published_at: Option<String>,
+    pub image_url: Option<String>,
+    pub content: Option<String>,
+    /// Characters of the full article newsapi left out of `content`.
+    pub content_remaining: Option<usize>,
+}
+
+impl NewsCardData {
+    pub fn new(title: &str, url: &str) -> Self {
+        NewsCardData {
+            title: title.to_string(),
+            description: None,
+            url: url.to_string(),
+            url_valid: is_web_url(url),
+            id: article_id(url),
+            source: String::new(),
+            source_id: None,
+            author: None,
+            published_at: None,
+            image_url: None,
+            content: None,
+            content_remaining: None,
+        }
+    }
+
+    /// Stable identity used to key per-article state, see [`article_id`].
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+}
+
+impl From<&Article> for NewsCardData {
+    fn from(a: &Article) -> Self {
+        let (content, content_remaining) = match a.content().map(split_content_marker) {
+            Some((content, remaining)) => (Some(decode_entities(content)), remaining),
+            None => (None, None),
+        };
+        NewsCardData {
+            title: decode_entities(a.title()),
+            url: a.url().to_string(),
+            url_valid: is_web_url(a.url()),
+            id: article_id(a.url()),
+            source: a.source().to_string(),
+            source_id: a.source_id().map(|id| id.to_string()),
+            author: a.author().map(decode_entities),
+            // normalized to `...Z` so dates compare correctly as strings
+            published_at: a
+                .published_at_utc()
+                .map(|p| p.to_rfc3339_opts(SecondsFormat::Secs, true)),
+            description: a.description().map(decode_entities),
+            image_url: a.image_url().filter(|u| !u.is_empty()).map(|u| u.to_string()),
+            content,
+            content_remaining,
+        }
+    }
 }
 
 pub struct Headlines {
     pub articles: Vec<NewsCardData>,
     pub config: HeadlinesConfig,
     pub api_key_initialized: bool,
-    pub news_rx: Option<Receiver<NewsCardData>>,
+    /// Set when a blank key was submitted, until the key is edited again.
+    api_key_missing: bool,
+    /// newsapi turned the key down, so the key window is back up to correct it.
+    api_key_rejected: bool,
+    pub total_results: Option<u64>,
+    pub news_rx: Option<Receiver<FetchEvent>>,
     pub app_tx: Option<SyncSender<Msg>>,
+    pub translator: Box<dyn Translator>,
+    pub post_processor: Box<dyn PostProcess>,
+    pub translations: HashMap<String, String>,
+    pub expanded: HashSet<String>,
+    pub full_descriptions: HashSet<String>,
+    pub(crate) deferred: DeferredText,
+    #[cfg(not(target_arch = "wasm32"))]
+    notifier: HeadlineNotifier,
+    pub show_settings: bool,
+    pub show_stats: bool,
+    pub show_about: bool,
+    /// Whether the feed holds search results rather than the plain home feed.
+    searching: bool,
+    /// Lets fetches and timers finishing in the background wake an idle UI.
+    pub(crate) egui_ctx: Option<Context>,
+    /// Shared by the image downloads and the reader.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub(crate) http: ureq::Agent,
+    waker: Waker,
+    pub pending_action: Option<SettingsAction>,
+    previous_ids: HashSet<String>,
+    new_articles: HashMap<String, Option<f64>>,
+    /// The first card on screen as of the last frame.
+    top_visible: Option<String>,
+    /// The card a refresh was reading from, scrolled back to while the feed reloads.
+    scroll_anchor: Option<String>,
+    /// How far the feed has been pulled down past its top, see [`Headlines::track_pull`].
+    pub(crate) pull: f32,
+    /// The blend categories being typed in settings, until Enter applies them.
+    pub(crate) blend_input: Option<String>,
+    pub(crate) blend_error: Option<String>,
+    /// Blended categories the last fetch couldn't load, the feed shows the rest.
+    pub failed_categories: Vec<Category>,
+    /// What the last frame panicked with, the crash screen shows until it's dismissed.
+    pub(crate) crash: Option<String>,
+    /// A refresh was sent and the feed on screen stays until its answer arrives.
+    refreshing: bool,
+    /// The api key `NEWSAPI_KEY` or the config file supplied, and the stored key it hid.
+    api_key_override: Option<(String, String)>,
+    /// The card the arrow keys moved to, by id so it survives articles coming in above it.
+    selected_card: Option<String>,
+    /// The selection moved this frame and should be scrolled into view.
+    selection_moved: bool,
+    /// Enter was pressed on the selected card.
+    open_selected: bool,
+    /// A touch was seen, so card actions stop waiting for a hover that can't happen.
+    touch_seen: bool,
+    pub last_error: Option<String>,
+    pub fetch_state: FetchState,
+    pub requests_succeeded: u32,
+    pub requests_failed: u32,
+    /// Per-source fetch outcomes this session, behind the dots in the source picker.
+    pub(crate) source_health: Vec<(SourceKind, SourceHealth)>,
+    pub last_fetch_at: Option<f64>,
+    next_refresh_at: Option<f64>,
+    toast: Option<(String, f64)>,
+    /// False when eframe gave us no storage, so the config only lives in memory.
+    pub persistent: bool,
+    /// [`STORAGE_KEY`], or the profile's own key, see [`storage_key`].
+    pub storage_key: String,
+    pub(crate) connection_test: ConnectionTest,
+    /// Kicks off the first fetch once the first frame runs, so it never races the canvas.
+    #[cfg(target_arch = "wasm32")]
+    startup_fetch: Option<Box<dyn FnOnce()>>,
+    /// Handles queued messages on wasm, see [`Headlines::send`].
+    #[cfg(target_arch = "wasm32")]
+    dispatch: Option<Box<dyn FnMut()>>,
+    #[cfg(not(target_arch = "wasm32"))]
+    pub(crate) extractor: Option<Arc<dyn Extractor>>,
+    #[cfg(not(target_arch = "wasm32"))]
+    pub(crate) reader: Option<ReaderPane>,
+    pub view: View,
+    pub bookmark_filter: String,
+    pub(crate) bookmark_files: BookmarkFiles,
+    pub(crate) sources_list: SourceList,
+    /// Feeds beside the main one while split view is on, one for now.
+    pub(crate) split: Vec<FeedPane>,
+    pub(crate) paging: Paging,
+    /// What the toolbar needs with every button out, as last measured. Nothing until it's
+    /// first drawn, so it starts out with every button out to measure.
+    toolbar_width: f32,
+    /// Text in the search box, sent to the source on Enter.
+    pub search: String,
+    /// Name typed in settings for the next saved search.
+    pub new_search_name: String,
+    /// Hides the panels and header so only the cards are left.
+    pub reading_mode: bool,
+    pub images: ImageLoader,
+    /// Image url shown in the lightbox, and whether it has been drawn yet.
+    lightbox: Option<(String, bool)>,
+    #[cfg(not(target_arch = "wasm32"))]
+    pub window: WindowOptions,
+    #[cfg(not(target_arch = "wasm32"))]
+    pending_window_size: Option<Vec2>,
+    /// What the window title was last built from, so it's only rebuilt when that changes.
+    #[cfg(not(target_arch = "wasm32"))]
+    window_title_key: Option<(String, usize, usize, Option<String>)>,
+}
+
+impl Default for Headlines {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Headlines {
@@ -60,110 +659,415 @@ impl Headlines {
         Headlines {
             articles: vec![],
             api_key_initialized: Default::default(),
+            api_key_missing: false,
+            api_key_rejected: false,
             config: Default::default(),
+            total_results: None,
             news_rx: None,
             app_tx: None,
+            translator: Box::new(NoopTranslator),
+            post_processor: Box::new(NoPostProcess),
+            translations: HashMap::new(),
+            expanded: HashSet::new(),
+            full_descriptions: HashSet::new(),
+            deferred: DeferredText::default(),
+            #[cfg(not(target_arch = "wasm32"))]
+            notifier: HeadlineNotifier::default(),
+            show_settings: false,
+            show_stats: false,
+            show_about: false,
+            searching: false,
+            egui_ctx: None,
+            #[cfg(not(target_arch = "wasm32"))]
+            http: http_agent(false),
+            waker: Waker::default(),
+            pending_action: None,
+            previous_ids: HashSet::new(),
+            new_articles: HashMap::new(),
+            top_visible: None,
+            scroll_anchor: None,
+            pull: 0.0,
+            blend_input: None,
+            blend_error: None,
+            failed_categories: Vec::new(),
+            crash: None,
+            refreshing: false,
+            api_key_override: None,
+            selected_card: None,
+            touch_seen: false,
+            selection_moved: false,
+            open_selected: false,
+            last_error: None,
+            fetch_state: FetchState::default(),
+            requests_succeeded: 0,
+            requests_failed: 0,
+            source_health: Vec::new(),
+            last_fetch_at: None,
+            next_refresh_at: None,
+            toast: None,
+            view: View::Feed,
+            bookmark_filter: String::new(),
+            bookmark_files: BookmarkFiles::default(),
+            sources_list: SourceList::default(),
+            split: Vec::new(),
+            paging: Paging::default(),
+            toolbar_width: 0.0,
+            search: String::new(),
+            new_search_name: String::new(),
+            reading_mode: false,
+            images: ImageLoader::default(),
+            lightbox: None,
+            persistent: true,
+            storage_key: storage_key(),
+            connection_test: ConnectionTest::default(),
+            #[cfg(target_arch = "wasm32")]
+            startup_fetch: None,
+            #[cfg(target_arch = "wasm32")]
+            dispatch: None,
+            #[cfg(not(target_arch = "wasm32"))]
+            extractor: None,
+            #[cfg(not(target_arch = "wasm32"))]
+            reader: None,
+            #[cfg(not(target_arch = "wasm32"))]
+            window: WindowOptions::load(),
+            #[cfg(not(target_arch = "wasm32"))]
+            pending_window_size: None,
+            #[cfg(not(target_arch = "wasm32"))]
+            window_title_key: None,
         }
     }
+
+    pub fn with_translator(mut self, translator: impl Translator + 'static) -> Self {
+        self.translator = Box::new(translator);
+        self
+    }
+
+    pub fn with_post_processor(mut self, post_processor: impl PostProcess + 'static) -> Self {
+        self.post_processor = Box::new(post_processor);
+        self
+    }
+
+    /// Enables the in-app reader, which is off until an extractor is provided.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn with_extractor(mut self, extractor: impl Extractor + 'static) -> Self {
+        self.extractor = Some(Arc::new(extractor));
+        self
+    }
+
     pub fn init(mut self, cc: &CreationContext) -> Self {
+        self.egui_ctx = Some(cc.egui_ctx.clone());
         if let Some(storage) = cc.storage {
-            self.config = eframe::get_value(storage, "headlines").unwrap_or_default();
-            self.api_key_initialized = !self.config.api_key.is_empty();
+            self.config = eframe::get_value(storage, &self.storage_key).unwrap_or_default();
+        } else {
+            tracing::warn!("no storage available, settings won't persist");
+            self.persistent = false;
+            let now = cc.egui_ctx.input().time;
+            self.show_toast(t("storage_unavailable"), now);
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let stored_key = self.config.api_key.clone();
+            if let Some(key) = crate::config_file::apply_overrides(&mut self.config) {
+                // a key an older version stored from the env or file is dropped from storage
+                let stored_key = if stored_key == key { String::new() } else { stored_key };
+                self.api_key_override = Some((key, stored_key));
+            }
+            self.update_http_agent();
+        }
+        self.api_key_initialized = !self.config.api_key.trim().is_empty();
+        match self.config.startup_view {
+            StartupView::Category(category) => self.config.category = category,
+            StartupView::LastSearch => {
+                if let Some(search) = &self.config.last_search {
+                    self.search = search.clone();
+                    self.searching = true;
+                }
+            }
+            StartupView::LastCategory => {}
+        }
+        if self.config.country.is_none() {
+            self.config.country = Some(detect_country().unwrap_or_default());
         }
 
-        let api_key = self.config.api_key.to_string();
+        let query = self.feed_query();
+        self.sources_list.applied = query.sources.clone();
+        let mut target = FetchTarget {
+            query,
+            api_key: self.config.api_key.to_string(),
+            source_kind: self.config.source,
+            rss_url: self.config.rss_url.clone(),
+            #[cfg(not(target_arch = "wasm32"))]
+            accept_invalid_certs: self.config.danger_accept_invalid_certs,
+        };
 
-        let (news_tx, news_rx) = channel();
+        let (news_tx, news_rx) = sync_channel(self.config.news_channel_capacity.max(1));
         let (app_tx, app_rx) = sync_channel(1);
 
         self.app_tx = Some(app_tx);
         self.news_rx = Some(news_rx);
 
-
+        let repaint = cc.egui_ctx.clone();
         #[cfg(target_arch = "wasm32")]
-        let api_key_web = api_key.clone();
+        let fetcher = Rc::new(RefCell::new(WebFetcher::new(target.source(), news_tx, repaint)));
 
         #[cfg(target_arch = "wasm32")]
-        let news_tx_web = news_tx.clone();
+        let fetcher_web = fetcher.clone();
 
         #[cfg(not(target_arch = "wasm32"))]
         thread::spawn(move || {
-            if !api_key.is_empty() {
-                fetch_news(&api_key, &news_tx);
-            } else {
-                loop {
-                    match app_rx.recv() {
-                        Ok(Msg::ApiKeySet(api_key)) => {
-                            fetch_news(&api_key, &news_tx);
-                        }
-                        Ok(Msg::Refresh) => {
-                            fetch_news(&api_key, &news_tx);
-                        }
-                        Err(e) => {
-                            tracing::error!("failed receiving msg: {}", e);
-                        }
+            let mut fetcher = NativeFetcher::new(target.source(), news_tx, repaint);
+            if !target.api_key.is_empty() {
+                fetcher.spawn(target.query.clone());
+            }
+            loop {
+                match app_rx.recv() {
+                    Ok(msg) => target.apply(msg, &mut fetcher),
+                    Err(e) => {
+                        tracing::error!("failed receiving msg: {}", e);
+                        break;
                     }
                 }
             }
         });
 
         #[cfg(target_arch = "wasm32")]
-        gloo_timers::callback::Timeout::new(10, move || {
-            wasm_bindgen_futures::spawn_local(async {
-                fetch_web(api_key_web, news_tx_web).await;
-            });
-        })
-        .forget();
+        let initial_query = target.query.clone();
 
         #[cfg(target_arch = "wasm32")]
-        gloo_timers::callback::Interval::new(500, move || match app_rx.try_recv() {
-            Ok(Msg::ApiKeySet(api_key)) => {
-                wasm_bindgen_futures::spawn_local(fetch_web(api_key.clone(), news_tx.clone()));
-            }
-            Ok(Msg::Refresh) => {
-                let api_key = api_key.clone();
-                wasm_bindgen_futures::spawn_local(fetch_web(api_key, news_tx.clone()));
-            }
-            Err(e) => {
-                tracing::error!("failed receiving msg: {}", e);
-            }
-        })
-        .forget();
+        {
+            let delay = self.config.startup_fetch_delay_ms;
+            self.startup_fetch = Some(Box::new(move || {
+                gloo_timers::callback::Timeout::new(delay, move || {
+                    fetcher_web.borrow_mut().spawn(initial_query);
+                })
+                .forget();
+            }));
+        }
+
+        // wasm runs everything on one thread, so rather than polling the channel
+        // messages are handled as soon as `send` queues them
+        #[cfg(target_arch = "wasm32")]
+        {
+            self.dispatch = Some(Box::new(move || loop {
+                match app_rx.try_recv() {
+                    Ok(msg) => target.apply(msg, &mut fetcher.borrow_mut()),
+                    Err(std::sync::mpsc::TryRecvError::Empty) => break,
+                    Err(e) => {
+                        tracing::error!("failed receiving msg: {}", e);
+                        break;
+                    }
+                }
+            }));
+        }
 
         self.configure_fonts(&cc.egui_ctx);
 
         self
     }
 
+    /// Starts the initial wasm fetch; called from the first `update`.
+    #[cfg(target_arch = "wasm32")]
+    pub fn start_fetching(&mut self) {
+        if let Some(start) = self.startup_fetch.take() {
+            start();
+        }
+    }
+
+    /// Queues `msg` for the fetcher. The native worker thread picks it up
+    /// on its own, on wasm it's handled before this returns.
+    pub fn send(&mut self, msg: Msg) {
+        if let Some(tx) = &self.app_tx {
+            tx.send(msg).expect("Failed sending app message");
+        }
+        #[cfg(target_arch = "wasm32")]
+        if let Some(dispatch) = &mut self.dispatch {
+            dispatch();
+        }
+    }
+
     pub fn configure_fonts(&self, ctx: &Context) {
-        let mut font_def = FontDefinitions::default();
-        font_def.font_data.insert(
-            "MesloLGS".to_string(),
-            FontData::from_static(include_bytes!("../../MesloLGS_NF_Regular.ttf")),
-        );
-        font_def
-            .families
-            .get_mut(&FontFamily::Proportional)
-            .unwrap()
-            .insert(0, "MesloLGS".to_string());
-        ctx.set_fonts(font_def);
-    }
-    pub fn render_news_cards(&self, ui: &mut Ui) {
+        ctx.set_fonts(with_meslo(FontDefinitions::default()));
+    }
+
+    pub fn render_news_cards(&mut self, ui: &mut Ui) {
+        let mut translate_id = None;
+        let mut toggled_id = None;
+        let mut toggled_description = None;
+        let mut toggled_bookmark = None;
+        let mut toggled_queued = None;
+        let mut toggled_pin = None;
+        let mut opened_image = None;
+        let mut copied = None;
+        let mut opened = None;
+        let mut clicked = None;
+        #[cfg(not(target_arch = "wasm32"))]
+        let mut read_here = None;
+        let now = ui.input().time;
+        let today = Utc::now();
+        let hover_actions = self.hover_actions(ui);
+        let watch = watch_terms(&self.config.watchlist);
+        let spacing = self.config.density.spacing();
+        let mut top_visible = None;
+        let mut anchor_at = None;
+        let tags = self.config.source_tags && self.config.source == SourceKind::NewsApi;
+        if tags && !self.config.api_key.trim().is_empty() {
+            self.sources_list.ensure_loaded(self.config.api_key.trim());
+        }
         for a in &self.articles {
-            ui.add_space(PADDING);
+            if self.is_too_old(a, today) {
+                continue;
+            }
+            let card_at = Rect::from_min_size(ui.cursor().min, Vec2::ZERO);
+            if self.scroll_anchor.as_deref() == Some(a.id()) {
+                anchor_at = Some(card_at);
+            }
+            let selected = self.selected_card.as_deref() == Some(a.id());
+            if selected && self.open_selected {
+                clicked = Some(a.id().to_string());
+            }
+            ui.add_space(spacing);
+
+            //render new badge
+            if let Some(since) = self.new_articles.get_mut(a.id()) {
+                let shown_at = *since.get_or_insert(now);
+                let fade = 1.0 - ((now - shown_at) / NEW_BADGE_SECS) as f32;
+                if fade > 0.0 {
+                    let badge = RichText::new(t("new_badge")).small().strong();
+                    ui.label(badge.color(Color32::from_rgb(0, 200, 0).linear_multiply(fade)));
+                }
+            }
 
             //render title
-            let title = format!("▶ {}", a.title);
-            if self.config.dark_mode {
-                ui.colored_label(WHITE, title);
+            let color = if self.config.dark_mode { WHITE } else { BLACK };
+            // read cards fold down to their title, like every card in compact mode
+            let folded_read = self.config.collapse_read && self.is_read(a.id());
+            let collapsible = self.config.compact || folded_read;
+            let expanded = !collapsible || self.expanded.contains(a.id());
+            let marker = if collapsible && expanded { icon("▼") } else { icon("▶") };
+            let text = if self.config.strip_source_suffix {
+                strip_source_suffix(&a.title, &a.source)
+            } else {
+                a.title.clone()
+            };
+            let text = format!("{} {}", marker, text);
+            // folded lines keep read and unread apart at a glance
+            let (line, color) = if collapsible && !expanded {
+                let (line, theme) = if self.is_read(a.id()) {
+                    (self.config.read_line, ui.visuals().weak_text_color())
+                } else {
+                    (self.config.unread_line, color)
+                };
+                (line, line.color_or(theme))
+            } else {
+                (LineStyle::default(), color)
+            };
+            let matches = watch_matches(&text, &watch);
+            let text: WidgetText = if matches.is_empty() {
+                line.rich(RichText::new(text), color).into()
+            } else {
+                highlighted(ui, &text, &matches, color, &line).into()
+            };
+            // clickable either way so the context menu opens on right click
+            let title = ui.add(Label::new(text).sense(Sense::click()));
+            if (self.config.compact || !expanded) && title.clicked() {
+                toggled_id = Some(a.id().to_string());
+            } else if title.clicked() {
+                clicked = Some(a.id().to_string());
+            }
+            let title = if self.config.debug_overlay {
+                title.on_hover_text(self.debug_fields(a))
             } else {
-                ui.colored_label(BLACK, title);
+                title
+            };
+            title.context_menu(|ui| {
+                if ui.add_enabled(a.url_valid, Button::new(t("copy_link"))).clicked() {
+                    ui.output().copied_text = a.url.clone();
+                    copied = Some(t("link_copied"));
+                    ui.close_menu();
+                }
+                let copy_image = Button::new(t("copy_image_url"));
+                if ui.add_enabled(a.image_url.is_some(), copy_image).clicked() {
+                    ui.output().copied_text = a.image_url.clone().unwrap_or_default();
+                    copied = Some(t("image_url_copied"));
+                    ui.close_menu();
+                }
+            });
+
+            //render translation
+            if self.config.translate_titles {
+                if let Some(translated) = self.translations.get(a.id()) {
+                    ui.label(RichText::new(translated).italics());
+                } else if ui.small_button(t("translate")).clicked() {
+                    translate_id = Some(a.id().to_string());
+                }
+            }
+
+            if !expanded {
+                ui.add_space(spacing);
+                continue;
+            }
+
+            //render thumbnail
+            if a.image_url.is_some() && self.config.low_bandwidth {
+                ui.label(RichText::new(icon("🖼")).weak()).on_hover_text(t("image_skipped"));
+            } else if let Some(image_url) = &a.image_url {
+                let slot = Vec2::new(ui.available_width(), THUMBNAIL_HEIGHT);
+                let visible = ui.is_rect_visible(Rect::from_min_size(ui.cursor().min, slot));
+                match self.images.get(&ImageKey::thumbnail(image_url), visible) {
+                    ImageState::Loaded(texture) => {
+                        let size = texture.size_vec2();
+                        // shrunk further for wide images in a narrow window
+                        let fit = ui.available_width() / size.x;
+                        let size = size * (THUMBNAIL_HEIGHT / size.y).min(fit);
+                        ui.add_space(spacing);
+                        let thumbnail = ImageButton::new(texture, size).frame(false);
+                        if ui.add(thumbnail).on_hover_text(t("view_image")).clicked() {
+                            opened_image = Some(image_url.clone());
+                        }
+                    }
+                    ImageState::Loading => {
+                        ui.add(Spinner::new());
+                    }
+                    ImageState::Failed(_) => {}
+                }
             }
 
             //render desc
-            ui.add_space(PADDING);
-            let desc = Label::new(RichText::new(&a.description).text_style(TextStyle::Button));
-            ui.add(desc);
+            if let Some(description) = &a.description {
+                ui.add_space(spacing);
+                let show_full = self.full_descriptions.contains(a.id());
+                let truncated = truncate_words(description, self.config.description_max_chars);
+                // a read-only text edit so the description can be selected and copied
+                let mut text = match &truncated {
+                    Some(short) if !show_full => short.as_str(),
+                    _ => description.as_str(),
+                };
+                let desc = TextEdit::multiline(&mut text)
+                    .id_source(("description", a.id()))
+                    .font(TextStyle::Button)
+                    .frame(false)
+                    .margin(Vec2::ZERO)
+                    .desired_rows(1)
+                    .desired_width(f32::INFINITY);
+                ui.add(desc);
+                if truncated.is_some() {
+                    let label = if show_full { t("less") } else { t("more") };
+                    if ui.small_button(label).clicked() {
+                        toggled_description = Some(a.id().to_string());
+                    }
+                }
+            }
+            if let Some(remaining) = a.content_remaining {
+                let remaining = format_count(remaining as u64);
+                let hint = tf("content_remaining", &[&remaining]);
+                ui.horizontal(|ui| {
+                    ui.label(RichText::new(hint).small().weak());
+                    #[cfg(not(target_arch = "wasm32"))]
+                    if self.extractor.is_some() && ui.small_button(t("read_here")).clicked() {
+                        read_here = Some(a.id().to_string());
+                    }
+                });
+            }
 
             //render hyperlink
             if self.config.dark_mode {
@@ -172,128 +1076,2497 @@ impl Headlines {
                 ui.style_mut().visuals.hyperlink_color = RED;
             }
 
-            ui.add_space(PADDING);
+            ui.add_space(spacing);
+            // the card so far plus the row about to be drawn
+            let hover_area = Rect::from_x_y_ranges(
+                card_at.left()..=ui.max_rect().right(),
+                card_at.top()..=ui.cursor().top() + ui.spacing().interact_size.y + spacing,
+            );
+            let show_actions = !hover_actions || selected || ui.rect_contains_pointer(hover_area);
             ui.with_layout(Layout::right_to_left().with_cross_align(Align::Min), |ui| {
-                ui.add(Hyperlink::from_label_and_url("read more ⤴", &a.url));
+                if a.url_valid {
+                    let link = Hyperlink::from_label_and_url(plain(t("read_more")), &a.url);
+                    if ui.add(link).clicked() {
+                        opened = Some(a.id().to_string());
+                    }
+                } else {
+                    ui.label(RichText::new(t("no_link")).weak())
+                        .on_hover_text(tf("invalid_url", &[&a.url]));
+                }
+                if show_actions {
+                    let bookmarked = self.is_bookmarked(a.id());
+                    let star = if bookmarked { icon("★") } else { icon("☆") };
+                    let tooltip = if bookmarked { t("remove_bookmark") } else { t("bookmark") };
+                    if ui.small_button(star).on_hover_text(tooltip).clicked() {
+                        toggled_bookmark = Some(a.id().to_string());
+                    }
+                    let queued = self.is_queued(a.id());
+                    let queue_tooltip = if queued { t("unqueue") } else { t("read_later") };
+                    let queue_btn = ui.selectable_label(queued, icon("📥"));
+                    if queue_btn.on_hover_text(queue_tooltip).clicked() {
+                        toggled_queued = Some(a.id().to_string());
+                    }
+                    let pinned = self.config.pinned.iter().any(|id| id == a.id());
+                    let pin_tooltip = if pinned { t("unpin") } else { t("pin") };
+                    let pin_btn = ui.selectable_label(pinned, icon("📌"));
+                    if pin_btn.on_hover_text(pin_tooltip).clicked() {
+                        toggled_pin = Some(a.id().to_string());
+                    }
+                    let style = self.config.citation_style;
+                    let cite_tooltip = tf("copy_citation", &[&style.name()]);
+                    if ui.small_button(icon("❝")).on_hover_text(cite_tooltip).clicked() {
+                        ui.output().copied_text = style.cite(a);
+                        copied = Some(t("citation_copied"));
+                    }
+                }
+                let published = a.published_at.as_deref().map(DateTime::parse_from_rfc3339);
+                if let Some(Ok(published)) = published {
+                    let published = published.with_timezone(&Utc);
+                    let (shown, other) = self.config.time_format.both(published, today);
+                    ui.label(RichText::new(shown).small().weak()).on_hover_text(other);
+                }
+                // most sources an article names aren't in the list, those just go untagged
+                let tag = tags.then(|| self.sources_list.tag(a.source_id.as_deref())).flatten();
+                if let Some((tag, hint)) = tag {
+                    ui.label(RichText::new(tag).small().weak()).on_hover_text(hint);
+                }
             });
-            ui.add_space(PADDING);
-            ui.add(Separator::default());
+            ui.add_space(spacing);
+            ui.add(Separator::default().spacing(self.config.density.separator_spacing()));
+            if selected {
+                let card = Rect::from_x_y_ranges(
+                    card_at.left()..=ui.max_rect().right(),
+                    card_at.top()..=ui.cursor().top(),
+                );
+                let stroke = Stroke::new(1.5, self.config.accent_color);
+                ui.painter().rect_stroke(card.expand(2.0), 4.0, stroke);
+                if self.selection_moved {
+                    ui.scroll_to_rect(card, None);
+                }
+            }
+            if top_visible.is_none() && ui.cursor().top() > ui.clip_rect().top() {
+                top_visible = Some(a.id().to_string());
+            }
+        }
+        self.top_visible = top_visible;
+        self.selection_moved = false;
+        self.open_selected = false;
+        // new articles landing above the card being read would push it down the page
+        if let Some(rect) = anchor_at {
+            ui.scroll_to_rect(rect, Some(Align::TOP));
+        }
+        if self.fetch_state != FetchState::Loading {
+            self.scroll_anchor = None;
         }
-    }
 
-    pub(crate) fn render_top_panel(&mut self, ctx: &Context, frame: &mut eframe::Frame) {
-        TopBottomPanel::top("top_panel").show(ctx, |ui| {
-            ui.add_space(10.0);
-            menu::bar(ui, |ui| {
-                ui.with_layout(Layout::left_to_right(), |ui| {
-                    ui.add(Label::new(
-                        RichText::new("📓").text_style(TextStyle::Heading),
-                    ));
-                });
-                ui.with_layout(Layout::right_to_left(), |ui| {
-                    //conditional render
-                    if !cfg!(target_arch = "wasm32") {
-                        let close_btn =
-                            ui.add(Button::new(RichText::new("❌").text_style(TextStyle::Body)));
-                        if close_btn.clicked() {
-                            frame.quit();
-                        }
-                    }
+        self.new_articles
+            .retain(|_, since| since.map_or(true, |shown_at| now - shown_at < NEW_BADGE_SECS));
 
-                    let refresh_btn =
-                        ui.add(Button::new(RichText::new("🔄").text_style(TextStyle::Body)));
-                    if refresh_btn.clicked() {
-                        self.articles.clear();
-                        if let Some(tx) = &self.app_tx {
-                            tx.send(Msg::Refresh).expect("Failed sending refresh event");
-                        }
-                    }
+        if let Some(id) = translate_id {
+            self.translate_title(&id);
+        }
+        if let Some(id) = toggled_id {
+            self.toggle_expanded(&id);
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(id) = read_here {
+            self.open_reader(&id);
+            opened = Some(id);
+        }
+        if let Some(id) = clicked {
+            opened = self.apply_click_action(ui, id);
+        }
+        if let Some(id) = opened {
+            self.config.stats.article_opened();
+            self.mark_read([id]);
+        }
+        if let Some(msg) = copied {
+            self.show_toast(msg, now);
+        }
+        if let Some(url) = opened_image {
+            self.lightbox = Some((url, false));
+        }
+        if let Some(id) = toggled_bookmark {
+            self.toggle_bookmark(&id);
+        }
+        if let Some(id) = toggled_queued {
+            self.toggle_read_later(&id);
+        }
+        if let Some(id) = toggled_pin {
+            self.toggle_pin(&id);
+        }
+        if let Some(id) = toggled_description {
+            if !self.full_descriptions.remove(&id) {
+                self.full_descriptions.insert(id);
+            }
+        }
+    }
 
-                    let theme_btn = ui.add(Button::new(
-                        RichText::new({
-                            if self.config.dark_mode {
-                                "🌞"
-                            } else {
-                                "🌙"
-                            }
-                        })
-                        .text_style(TextStyle::Body),
-                    ));
-                    if theme_btn.clicked() {
-                        self.config.dark_mode = !self.config.dark_mode;
-                    }
-                })
-            });
-            ui.add_space(10.0);
-        });
+    /// Raw fields behind a card, to help tell why it showed up where it did.
+    fn debug_fields(&self, a: &NewsCardData) -> String {
+        format!(
+            "id: {}\nsource: {} ({})\npublishedAt: {}\nquery: {} / {}",
+            a.id(),
+            a.source,
+            a.source_id.as_deref().unwrap_or("no id"),
+            a.published_at.as_deref().unwrap_or("none"),
+            self.config.source.name(),
+            self.config.category,
+        )
     }
 
-    pub fn preload_articles(&mut self) {
-        if let Some(rx) = &self.news_rx {
-            match rx.try_recv() {
-                Ok(news_data) => {
-                    self.articles.push(news_data);
-                }
-                Err(_e) => {
-                  // tracing::warn!("Error receiving news data: {}", e);
+    pub fn toggle_pin(&mut self, id: &str) {
+        if let Some(i) = self.config.pinned.iter().position(|p| p == id) {
+            self.config.pinned.remove(i);
+        } else {
+            self.config.pinned.push(id.to_string());
+        }
+        self.move_pinned_to_front();
+    }
+
+    /// Runs the configured click action, returning the id again if the article was opened.
+    fn apply_click_action(&mut self, ui: &Ui, id: String) -> Option<String> {
+        match self.config.click_action {
+            ClickAction::MarkRead => {
+                self.mark_read([id]);
+                None
+            }
+            #[cfg(not(target_arch = "wasm32"))]
+            ClickAction::Reader if self.extractor.is_some() => {
+                self.open_reader(&id);
+                Some(id)
+            }
+            ClickAction::Browser | ClickAction::Reader => {
+                let a = self.articles.iter().find(|a| a.id() == id)?;
+                if !a.url_valid {
+                    return None;
                 }
+                ui.output().open_url(&a.url);
+                Some(id)
             }
         }
     }
 
-    pub fn render_config(&mut self, ctx: &Context) {
-        CentralPanel::default().show(ctx, |_ui| {
-            Window::new("Configuration").show(ctx, |ui| {
-                ui.label("Enter your API_KEY for newsapi.org");
-                let text_input = ui.text_edit_singleline(&mut self.config.api_key);
-                if text_input.lost_focus() && ui.input().key_pressed(Key::Enter) {
-                    self.api_key_initialized = true;
-                    if let Some(tx) = &self.app_tx {
-                        tx.send(Msg::ApiKeySet(self.config.api_key.to_string())).expect("Failed sending ApiKeySet event");
-                    }
-                    // tracing::error!("api key set");
-                }
-                // tracing::error!("{}", &self.config.api_key);
-                ui.label("If you haven't registered for the API_KEY, head over to");
-                ui.hyperlink("https://newsapi.org");
-            });
-        });
+    fn has_reader(&self) -> bool {
+        #[cfg(not(target_arch = "wasm32"))]
+        return self.extractor.is_some();
+        #[cfg(target_arch = "wasm32")]
+        false
     }
-}
 
-#[cfg(not(target_arch = "wasm32"))]
-fn fetch_news(api_key: &str, news_tx: &Sender<NewsCardData>) {
-    if let Ok(response) = NewsAPI::new(&api_key).fetch() {
-        let response_articles = response.articles();
-        for a in response_articles.iter() {
-            let news = NewsCardData {
-                title: a.title().to_string(),
-                url: a.url().to_string(),
-                description: "default description".to_string(),
-            };
+    pub fn is_read(&self, id: &str) -> bool {
+        self.config.read.iter().any(|r| r == id)
+    }
 
-            if let Err(e) = news_tx.send(news) {
-                tracing::error!("Error sending news data: {}", e);
+    /// Remembers the articles as read, forgetting the oldest past `READ_HISTORY`.
+    pub fn mark_read(&mut self, ids: impl IntoIterator<Item = String>) {
+        for id in ids {
+            if !self.is_read(&id) {
+                self.config.read.push(id);
             }
         }
+        let excess = self.config.read.len().saturating_sub(READ_HISTORY);
+        self.config.read.drain(..excess);
     }
-}
 
-#[cfg(target_arch = "wasm32")]
-async fn fetch_web(api_key: String, news_tx: Sender<NewsCardData>) {
-    if let Ok(response) = NewsAPI::new(&api_key).fetch_web().await {
-        let resp_articles = response.articles();
-        for a in resp_articles.iter() {
-            let news = NewsCardData {
-                title: a.title().to_string(),
-                url: a.url().to_string(),
-                description: "default description".to_string(),
-            };
-            if let Err(e) = news_tx.send(news) {
-                tracing::error!("Error sending news data: {}", e);
+    /// Reorders the feed so no source has more than `max_run_per_source` articles in a
+    /// row, pulling the next article from another source forward where needed.
+    /// Otherwise the order is kept, and pinned articles stay in front. Runs once a fetch
+    /// or page is in, not per article, so cards don't move while they stream in.
+    pub(crate) fn diversify_sources(&mut self) {
+        let max_run = self.config.max_run_per_source;
+        if max_run > 0 {
+            let mut rest: VecDeque<NewsCardData> = self.articles.drain(..).collect();
+            while !rest.is_empty() {
+                let run = |source: &str| {
+                    let tail = self.articles.iter().rev();
+                    tail.take_while(|a| a.source == source).count()
+                };
+                let next = rest.iter().position(|a| run(&a.source) < max_run).unwrap_or(0);
+                self.articles.extend(rest.remove(next));
+            }
+        }
+        self.move_pinned_to_front();
+    }
+
+    /// Stable, so pinned and unpinned articles each keep their feed order.
+    fn move_pinned_to_front(&mut self) {
+        let pinned = &self.config.pinned;
+        self.articles.sort_by_key(|a| !pinned.iter().any(|id| id == a.id()));
+    }
+
+    fn toggle_expanded(&mut self, id: &str) {
+        if self.expanded.remove(id) {
+            return;
+        }
+        self.restore_deferred(id);
+        self.expanded.insert(id.to_string());
+    }
+
+    /// Gives the card back the description and content `lazy_descriptions` set aside.
+    pub(crate) fn restore_deferred(&mut self, id: &str) {
+        let a = match self.articles.iter_mut().find(|a| a.id() == id) {
+            Some(a) => a,
+            None => return,
+        };
+        if a.description.is_some() || a.content.is_some() {
+            return;
+        }
+        if let Some((description, content)) = self.deferred.take(id) {
+            a.description = description;
+            a.content = content;
+        }
+    }
+
+    fn translate_title(&mut self, id: &str) {
+        if let Some(a) = self.articles.iter().find(|a| a.id() == id) {
+            match self.translator.translate(&a.title) {
+                Some(translated) => {
+                    self.translations.insert(a.id().to_string(), translated);
+                }
+                None => tracing::warn!("no translation available for: {}", a.title),
             }
         }
-    } else {
-        tracing::error!("failed fetching news");
+    }
+
+    /// Returns whether the close button was clicked.
+    pub(crate) fn render_top_panel(&mut self, ctx: &Context) -> bool {
+        let mut quit = false;
+        TopBottomPanel::top("top_panel").show(ctx, |ui| {
+            ui.add_space(10.0);
+            quit = self.render_toolbar(ui).quit;
+            ui.add_space(PADDING);
+            self.render_category_tabs(ui);
+            self.render_search(ui);
+            self.render_saved_search_chips(ui);
+            ui.add_space(PADDING);
+        });
+        quit
+    }
+
+    /// The logo on the left and the buttons on the right. When they wouldn't all fit
+    /// the toggles move into a menu, so the row fits down to the minimum window size.
+    fn render_toolbar(&mut self, ui: &mut Ui) -> Toolbar {
+        let narrow = ui.available_width() < self.toolbar_width;
+        let toolbar = menu::bar(ui, |ui| {
+            let logo = ui.with_layout(Layout::left_to_right(), |ui| {
+                let logo = ui.add(
+                    Label::new(RichText::new(icon("📓")).text_style(TextStyle::Heading))
+                        .sense(Sense::click()),
+                );
+                logo.on_hover_text(t("quick_actions_hint"))
+                    .context_menu(|ui| self.render_quick_actions(ui));
+                if self.config.low_bandwidth {
+                    ui.label(RichText::new(t("data_saver_on")).small().color(AMBER));
+                }
+            });
+            let mut quit = false;
+            let buttons = ui.with_layout(Layout::right_to_left(), |ui| {
+                //conditional render
+                if !cfg!(target_arch = "wasm32") {
+                    quit = icon_button(ui, "❌", t("close")).clicked();
+                }
+
+                let refresh_btn = icon_button(ui, "🔄", t("refresh"));
+                if refresh_btn.clicked() {
+                    let force = ui.input().modifiers.shift;
+                    self.refresh(ui.input().time, force);
+                }
+
+                let settings_btn = icon_button(ui, "⚙", t("settings"));
+                if settings_btn.clicked() {
+                    self.show_settings = !self.show_settings;
+                }
+
+                if narrow {
+                    ui.menu_button(icon("…"), |ui| self.render_toggles(ui, menu_item))
+                        .response
+                        .on_hover_text(t("more_actions"));
+                } else {
+                    self.render_toggles(ui, icon_button);
+                }
+            });
+            Toolbar {
+                quit,
+                logo: logo.response.rect,
+                buttons: buttons.response.rect,
+            }
+        })
+        .inner;
+        if !narrow {
+            let spacing = ui.spacing().item_spacing.x;
+            self.toolbar_width = toolbar.logo.width() + spacing + toolbar.buttons.width();
+            if toolbar.logo.intersects(toolbar.buttons) {
+                // collapse right away rather than at the next input
+                ui.ctx().request_repaint();
+            }
+        }
+        toolbar
+    }
+
+    /// The buttons that switch views and settings on and off, drawn with `button`.
+    fn render_toggles(&mut self, ui: &mut Ui, button: ToolbarButton) {
+        if !cfg!(target_arch = "wasm32") {
+            let notify_icon = if self.config.notify_new_headlines { "🔔" } else { "🔕" };
+            let notify_btn = button(ui, notify_icon, t("toggle_notifications"));
+            if notify_btn.clicked() {
+                self.config.notify_new_headlines = !self.config.notify_new_headlines;
+            }
+        }
+
+        let bookmarks_icon = if self.view == View::Bookmarks { "📰" } else { "🔖" };
+        let bookmarks_btn = button(ui, bookmarks_icon, t("toggle_bookmarks"));
+        if bookmarks_btn.clicked() {
+            self.view = match self.view {
+                View::Bookmarks => View::Feed,
+                _ => View::Bookmarks,
+            };
+        }
+
+        let queue_icon = if self.view == View::ReadLater { "📰" } else { "📥" };
+        let queue_btn = button(ui, queue_icon, t("toggle_read_later"));
+        if queue_btn.clicked() {
+            self.view = match self.view {
+                View::ReadLater => View::Feed,
+                _ => View::ReadLater,
+            };
+        }
+
+        let saver_icon = if self.config.low_bandwidth { "📵" } else { "📶" };
+        let saver_btn = button(ui, saver_icon, t("toggle_data_saver"));
+        if saver_btn.clicked() {
+            self.config.low_bandwidth = !self.config.low_bandwidth;
+            self.send(Msg::LowBandwidth(self.config.low_bandwidth));
+        }
+
+        let split_btn = button(ui, "◫", t("toggle_split"));
+        if split_btn.clicked() {
+            self.config.split_view = !self.config.split_view;
+        }
+
+        let digest_icon = if self.view == View::Digest { "📰" } else { "☕" };
+        let digest_btn = button(ui, digest_icon, t("toggle_digest"));
+        if digest_btn.clicked() {
+            self.view = match self.view {
+                View::Digest => View::Feed,
+                _ => View::Digest,
+            };
+        }
+
+        let compact_btn = button(ui, "☰", t("toggle_compact"));
+        if compact_btn.clicked() {
+            self.config.compact = !self.config.compact;
+        }
+
+        let translate_btn = button(ui, "🌐", t("toggle_translate"));
+        if translate_btn.clicked() {
+            self.config.translate_titles = !self.config.translate_titles;
+        }
+
+        let theme_icon = if self.config.dark_mode { "🌞" } else { "🌙" };
+        let theme_btn = button(ui, theme_icon, t("toggle_theme"));
+        if theme_btn.clicked() {
+            self.config.dark_mode = !self.config.dark_mode;
+        }
+
+        let copy_btn = button(ui, "📋", t("copy_feed_text"));
+        if copy_btn.clicked() && !self.articles.is_empty() {
+            ui.output().copied_text = feed_as_text(&self.articles);
+            self.show_toast(t("feed_copied"), ui.input().time);
+        }
+    }
+
+    fn render_search(&mut self, ui: &mut Ui) {
+        ui.horizontal(|ui| {
+            let search = ui.add(
+                TextEdit::singleline(&mut self.search)
+                    .id(Id::new(SEARCH_BOX_ID))
+                    .hint_text(t("search")),
+            );
+            let submitted = search.lost_focus() && ui.input().key_pressed(Key::Enter);
+
+            let mut scope = self.config.search_scope;
+            for s in SearchScope::ALL {
+                ui.selectable_value(&mut scope, s, s.name())
+                    .on_hover_text(s.description());
+            }
+            let scope_changed = scope != self.config.search_scope;
+            self.config.search_scope = scope;
+
+            if submitted || (scope_changed && !self.search.trim().is_empty()) {
+                self.submit_search();
+            }
+        });
+    }
+
+    /// Sends the search box to the source, an empty box goes back to the plain feed.
+    pub fn submit_search(&mut self) {
+        let search = Some(self.search.trim().to_string()).filter(|s| !s.is_empty());
+        if search.is_some() {
+            self.config.stats.search_run();
+        }
+        self.searching = search.is_some();
+        self.config.last_search = search.clone();
+        self.clear_feed();
+        self.previous_ids.clear();
+        #[cfg(not(target_arch = "wasm32"))]
+        self.notifier.reset();
+        self.send(Msg::SearchChanged {
+            search,
+            scope: self.config.search_scope,
+            category: self.config.category,
+        });
+    }
+
+    fn render_category_tabs(&mut self, ui: &mut Ui) {
+        let unread = self.unread_counts();
+        ui.horizontal_wrapped(|ui| {
+            for (i, category) in Category::ALL.iter().enumerate() {
+                let selected = self.config.category == *category
+                    || self.config.blend_categories.contains(category);
+                let tab = match unread.iter().find(|(c, _)| c == category) {
+                    Some((_, n)) if *n > 0 => {
+                        format!("{} {} ({})", i + 1, category.name(), format_count(*n as u64))
+                    }
+                    _ => format!("{} {}", i + 1, category.name()),
+                };
+                let tab = ui.selectable_label(selected, tab).on_hover_text(t("blend_tab_hint"));
+                if tab.clicked() && ui.input().modifiers.command {
+                    self.toggle_blended(*category);
+                } else if tab.clicked() {
+                    self.set_category(*category);
+                }
+            }
+        });
+    }
+
+    pub fn handle_category_keys(&mut self, ctx: &Context) {
+        if ctx.wants_keyboard_input() {
+            return;
+        }
+        const KEYS: [Key; 7] = [
+            Key::Num1,
+            Key::Num2,
+            Key::Num3,
+            Key::Num4,
+            Key::Num5,
+            Key::Num6,
+            Key::Num7,
+        ];
+        let pressed = KEYS.iter().position(|k| ctx.input().key_pressed(*k));
+        if let Some(i) = pressed {
+            self.set_category(Category::ALL[i]);
+        }
+    }
+
+    /// Ctrl+K / Cmd+K or '/' jump to the search box, like most apps with one.
+    pub fn handle_search_focus_key(&mut self, ctx: &Context) {
+        if ctx.wants_keyboard_input() {
+            return;
+        }
+        let mut input = ctx.input_mut();
+        let slash = input.events.iter().any(|e| *e == Event::Text("/".into()));
+        let command_k = input.modifiers.command && input.key_pressed(Key::K);
+        if !slash && !command_k {
+            return;
+        }
+        // The box takes focus this frame, it mustn't also receive the '/' that focused it.
+        input.events.retain(|e| *e != Event::Text("/".into()));
+        drop(input);
+        ctx.memory().request_focus(Id::new(SEARCH_BOX_ID));
+    }
+
+    /// Up and down arrows move a selection through the feed's cards, Enter opens the
+    /// selected one and Ctrl+C / Cmd+C copies its "Title — URL". Text fields with focus
+    /// keep all of these, so copying text out of them works as always.
+    pub fn handle_card_keys(&mut self, ctx: &Context) {
+        if ctx.wants_keyboard_input() || self.view != View::Feed || self.articles.is_empty() {
+            return;
+        }
+        let input = ctx.input();
+        let step = match (input.key_pressed(Key::ArrowDown), input.key_pressed(Key::ArrowUp)) {
+            (true, false) => Some(1),
+            (false, true) => Some(-1),
+            _ => None,
+        };
+        let (enter, escape) = (input.key_pressed(Key::Enter), input.key_pressed(Key::Escape));
+        let copy = input.events.contains(&Event::Copy);
+        let now = input.time;
+        drop(input);
+
+        let today = Utc::now();
+        let shown: Vec<&NewsCardData> =
+            self.articles.iter().filter(|a| !self.is_too_old(a, today)).collect();
+        if shown.is_empty() {
+            return;
+        }
+        let position = |id: &str| shown.iter().position(|a| a.id() == id);
+        let current = self.selected_card.as_deref().and_then(position);
+        if let Some(step) = step {
+            let next = match current {
+                Some(i) => i.saturating_add_signed(step).min(shown.len() - 1),
+                // start from the card at the top of the screen rather than the feed's top
+                None => self.top_visible.as_deref().and_then(position).unwrap_or(0),
+            };
+            self.selected_card = Some(shown[next].id().to_string());
+            self.selection_moved = true;
+        }
+        let current = match current {
+            Some(i) => shown[i].id().to_string(),
+            None => return,
+        };
+        if copy {
+            let Some(a) = self.articles.iter().find(|a| a.id() == current) else {
+                return;
+            };
+            ctx.output().copied_text = format!("{} — {}", a.title, a.url);
+            self.show_toast(t("card_copied"), now);
+        }
+        self.open_selected = enter;
+        if escape {
+            self.selected_card = None;
+        }
+    }
+
+    /// Toggles reading mode on `Z`, unless a text field has focus.
+    pub fn handle_reading_mode_key(&mut self, ctx: &Context) {
+        if ctx.wants_keyboard_input() || !ctx.input().key_pressed(Key::Z) {
+            return;
+        }
+        self.reading_mode = !self.reading_mode;
+        if self.reading_mode {
+            self.show_toast(t("reading_mode"), ctx.input().time);
+        }
+    }
+
+    pub fn set_category(&mut self, category: Category) {
+        if self.config.category == category {
+            return;
+        }
+        self.config.category = category;
+        self.config.stats.category_viewed(category);
+        self.clear_feed();
+        self.previous_ids.clear();
+        #[cfg(not(target_arch = "wasm32"))]
+        self.notifier.reset();
+        self.send(Msg::CategoryChanged(category));
+    }
+
+    pub fn set_source(&mut self, source: SourceKind) {
+        if self.config.source == source {
+            return;
+        }
+        self.config.source = source;
+        self.clear_feed();
+        self.previous_ids.clear();
+        #[cfg(not(target_arch = "wasm32"))]
+        self.notifier.reset();
+        self.send(Msg::SourceChanged(source));
+    }
+
+    pub fn set_country(&mut self, country: Country) {
+        if self.config.country == Some(country) {
+            return;
+        }
+        self.config.country = Some(country);
+        self.reset_feed();
+        self.send(Msg::CountryChanged(country));
+    }
+
+    /// The query the settings and the search box call for, as the fetcher starts with.
+    fn feed_query(&self) -> Query {
+        let mut query = Query::new(self.config.category);
+        query.scope = self.config.search_scope;
+        query.home = self.home_query();
+        query.country = self.config.country.unwrap_or_default();
+        query.page_size = page_size(self.config.low_bandwidth);
+        query.sources = normalize_filter(&self.config.source_filter);
+        query.blend = self.config.blend_categories.clone();
+        query.search = self.searching.then(|| self.search.clone());
+        query
+    }
+
+    /// Keywords for the /everything home feed, `None` while the home feed is top headlines.
+    fn home_query(&self) -> Option<String> {
+        match self.config.home_feed {
+            HomeFeed::TopHeadlines => None,
+            HomeFeed::Everything => {
+                let query = self.config.home_query.trim();
+                Some(if query.is_empty() { DEFAULT_HOME_QUERY } else { query }.to_string())
+            }
+        }
+    }
+
+    fn send_rss_url(&mut self) {
+        self.config.rss_url = self.config.rss_url.trim().to_string();
+        self.clear_feed();
+        self.previous_ids.clear();
+        #[cfg(not(target_arch = "wasm32"))]
+        self.notifier.reset();
+        self.send(Msg::RssUrlChanged(self.config.rss_url.clone()));
+    }
+
+    fn send_home_feed(&mut self) {
+        self.reset_feed();
+        self.send(Msg::HomeFeedChanged(self.home_query()));
+    }
+
+    /// Empties the feed ahead of a fetch for a different query, nothing in it is new then.
+    pub(crate) fn reset_feed(&mut self) {
+        self.clear_feed();
+        self.previous_ids.clear();
+        #[cfg(not(target_arch = "wasm32"))]
+        self.notifier.reset();
+    }
+
+    fn clear_feed(&mut self) {
+        self.previous_ids = self.articles.drain(..).map(|a| a.id).collect();
+        self.new_articles.clear();
+        self.total_results = None;
+        self.failed_categories.clear();
+        self.fetch_state = FetchState::Loading;
+        self.paging = Paging::default();
+        self.expanded.clear();
+        self.full_descriptions.clear();
+        self.deferred.clear();
+        self.refreshing = false;
+    }
+
+    /// Refetches the current feed unless it was fetched less than
+    /// `min_refresh_interval` seconds ago and `force` isn't set.
+    pub fn refresh(&mut self, now: f64, force: bool) {
+        let fresh = self
+            .last_fetch_at
+            .is_some_and(|at| now - at < self.config.min_refresh_interval);
+        if fresh && !force {
+            self.show_toast(t("up_to_date"), now);
+            return;
+        }
+        self.scroll_anchor = self.top_visible.clone();
+        // the feed is only cleared once the new one starts arriving, an unchanged one
+        // is left on screen untouched
+        self.refreshing = true;
+        self.fetch_state = FetchState::Loading;
+        self.send(Msg::Refresh);
+    }
+
+    /// Picks the time of the next automatic refresh, `auto_refresh_mins` from
+    /// `now` give or take `REFRESH_JITTER`.
+    pub fn schedule_auto_refresh(&mut self, now: f64) {
+        self.next_refresh_at = (self.config.auto_refresh_mins > 0).then(|| {
+            let interval = (self.config.auto_refresh_mins * 60) as f64;
+            let jitter = (random_unit() * 2.0 - 1.0) * REFRESH_JITTER;
+            now + interval.max(self.config.min_refresh_interval) * (1.0 + jitter)
+        });
+        if let (Some(at), Some(ctx)) = (self.next_refresh_at, &self.egui_ctx) {
+            self.waker.wake_after(ctx, at - now);
+        }
+    }
+
+    /// Whether something on screen is still changing from frame to frame.
+    pub fn is_animating(&self) -> bool {
+        #[cfg(not(target_arch = "wasm32"))]
+        if self.window.ticker || (self.config.notify_new_headlines && self.notifier.is_pending()) {
+            return true;
+        }
+        self.toast.is_some() || self.new_articles.values().any(Option::is_some)
+    }
+
+    pub fn auto_refresh(&mut self, now: f64) {
+        if self.next_refresh_at.is_some_and(|at| now >= at) {
+            self.next_refresh_at = None;
+            self.refresh(now, true);
+        }
+    }
+
+    /// Full-size preview of a thumbnail, closed with Escape or a click outside it.
+    pub fn render_lightbox(&mut self, ctx: &Context) {
+        let (url, drawn) = match &self.lightbox {
+            Some((url, drawn)) => (url.clone(), *drawn),
+            None => return,
+        };
+        let max_size = ctx.input().screen_rect().size() * 0.8;
+        let window = Window::new("lightbox")
+            .title_bar(false)
+            .collapsible(false)
+            .resizable(false)
+            .anchor(Align2::CENTER_CENTER, Vec2::ZERO)
+            .show(ctx, |ui| match self.images.get(&ImageKey::full(&url), true) {
+                ImageState::Loaded(texture) => {
+                    let size = texture.size_vec2();
+                    let scale = (max_size.x / size.x).min(max_size.y / size.y).min(1.0);
+                    ui.image(texture, size * scale);
+                }
+                ImageState::Loading => {
+                    ui.add(Spinner::new());
+                }
+                ImageState::Failed(e) => {
+                    ui.colored_label(RED, t("image_failed"));
+                    ui.label(RichText::new(e.as_str()).small().weak());
+                }
+            });
+
+        let input = ctx.input();
+        let clicked_outside = window.is_some_and(|w| {
+            let pointer = input.pointer.interact_pos();
+            input.pointer.any_click() && pointer.is_some_and(|p| !w.response.rect.contains(p))
+        });
+        // the click that opened the lightbox shouldn't also close it
+        if input.key_pressed(Key::Escape) || (drawn && clicked_outside) {
+            self.lightbox = None;
+        } else {
+            self.lightbox = Some((url, true));
+        }
+    }
+
+    /// Where the config lives, for backing it up or clearing it by hand. Native builds
+    /// can open eframe's storage directory, the web only has the localStorage key.
+    fn render_storage_location(&mut self, ui: &mut Ui) {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let mut failed = false;
+            ui.horizontal(|ui| {
+                let dir = storage_dir();
+                let button = ui.add_enabled(dir.is_some(), Button::new(t("open_storage_dir")));
+                let hint = match &dir {
+                    Some(dir) => dir.display().to_string(),
+                    None => t("storage_dir_unknown").to_string(),
+                };
+                if button.on_hover_text(hint).clicked() {
+                    if let Some(dir) = &dir {
+                        if let Err(e) = reveal(dir) {
+                            tracing::warn!("failed opening {}: {}", dir.display(), e);
+                            failed = true;
+                        }
+                    }
+                }
+                let key = tf("storage_key", &[&self.storage_key]);
+                ui.label(RichText::new(key).small().weak());
+            });
+            if failed {
+                self.show_toast(t("storage_dir_failed"), ui.input().time);
+            }
+        }
+        #[cfg(target_arch = "wasm32")]
+        ui.label(tf("storage_local", &[&self.storage_key]));
+    }
+
+    pub fn show_toast(&mut self, msg: &str, now: f64) {
+        self.toast = Some((msg.to_string(), now));
+    }
+
+    /// The logo's context menu, a hub for the actions people reach for most.
+    fn render_quick_actions(&mut self, ui: &mut Ui) {
+        if ui.button(t("refresh_now")).clicked() {
+            self.refresh(ui.input().time, false);
+            ui.close_menu();
+        }
+        let theme = if self.config.dark_mode { t("light_theme") } else { t("dark_theme") };
+        if ui.button(theme).clicked() {
+            self.config.dark_mode = !self.config.dark_mode;
+            ui.close_menu();
+        }
+        if ui.button(t("settings")).clicked() {
+            self.show_settings = true;
+            ui.close_menu();
+        }
+        if ui.button(t("about")).clicked() {
+            self.show_about = true;
+            ui.close_menu();
+        }
+        ui.separator();
+        if ui.button(t("reset_settings")).clicked() {
+            // goes through the same confirmation as the button in settings
+            self.show_settings = true;
+            self.pending_action = Some(SettingsAction::ResetSettings);
+            ui.close_menu();
+        }
+    }
+
+    pub fn render_about(&mut self, ctx: &Context) {
+        let mut open = self.show_about;
+        Window::new(t("about"))
+            .id(Id::new("about"))
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.vertical_centered(|ui| {
+                    ui.heading(format!("{} {}", icon("📓"), t("headlines")));
+                    ui.label(tf("version", &[&env!("CARGO_PKG_VERSION")]));
+                    ui.label(t("api_source"));
+                });
+            });
+        self.show_about = open;
+    }
+
+    pub fn render_toast(&mut self, ctx: &Context) {
+        let now = ctx.input().time;
+        if let Some((msg, shown_at)) = &self.toast {
+            if now - shown_at > TOAST_SECS {
+                self.toast = None;
+                return;
+            }
+            Area::new("toast")
+                .anchor(Align2::CENTER_BOTTOM, Vec2::new(0.0, -80.0))
+                .show(ctx, |ui| {
+                    Frame::popup(ui.style()).show(ui, |ui| {
+                        ui.label(msg.as_str());
+                    });
+                });
+        }
+    }
+
+    /// Handles the next fetch event, returning whether there was one.
+    pub fn preload_articles(&mut self, ctx: &Context) -> bool {
+        let mut received = false;
+        if let Some(rx) = &self.news_rx {
+            let event = rx.try_recv();
+            received = event.is_ok();
+            match event {
+                Ok(FetchEvent::Started { total }) => self.fetch_started(total, ctx.input().time),
+                Ok(FetchEvent::Done) => self.fetch_done(),
+                Ok(FetchEvent::NotModified { total, articles }) => {
+                    let now = ctx.input().time;
+                    if self.refreshing {
+                        // the articles on screen are these already, leave them be
+                        self.refreshing = false;
+                        self.record_success(now);
+                        self.fetch_state = FetchState::Done;
+                        self.show_toast(t("up_to_date"), now);
+                    } else {
+                        self.fetch_started(total, now);
+                        self.add_articles(articles);
+                        self.fetch_done();
+                    }
+                }
+                Ok(FetchEvent::Error(e)) => {
+                    if self.refreshing {
+                        self.clear_feed();
+                    }
+                    if let NewsApiError::BadApiKey = e {
+                        // the key window takes over and refetches once a key is entered
+                        self.api_key_rejected = true;
+                        self.api_key_initialized = false;
+                    }
+                    let e = e.to_string();
+                    self.fetch_state = FetchState::Failed(e.clone());
+                    self.last_error = Some(e);
+                    self.requests_failed += 1;
+                    self.record_fetch(false, ctx.input().time);
+                    self.page_failed();
+                    self.schedule_auto_refresh(ctx.input().time);
+                }
+                Ok(FetchEvent::Article(news_data)) => self.add_articles(vec![*news_data]),
+                Ok(FetchEvent::NextPage(articles)) => self.page_fetched(articles),
+                Ok(FetchEvent::CategoriesFailed(categories)) => {
+                    self.failed_categories = categories;
+                }
+                // nothing new yet
+                Err(_) => {}
+            }
+        }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        if self.config.notify_new_headlines {
+            self.notifier.flush();
+        }
+        received
+    }
+
+    /// The key to keep in eframe's storage: whatever was entered in the app, but never
+    /// one that came from the environment or the config file.
+    pub(crate) fn stored_api_key(&self) -> &str {
+        match &self.api_key_override {
+            Some((key, stored)) if *key == self.config.api_key => stored,
+            _ => &self.config.api_key,
+        }
+    }
+
+    fn fetch_started(&mut self, total: u64, now: f64) {
+        if self.refreshing {
+            self.clear_feed();
+        }
+        if let Some(ids) = self.category_ids_mut() {
+            ids.clear();
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        self.notifier.fetch_started(&self.config.seen);
+        self.total_results = Some(total);
+        self.record_success(now);
+    }
+
+    fn fetch_done(&mut self) {
+        self.deferred.flush();
+        self.fetch_state = FetchState::Done;
+        if self.config.max_run_per_source > 0 {
+            self.diversify_sources();
+        }
+    }
+
+    fn record_success(&mut self, now: f64) {
+        self.requests_succeeded += 1;
+        self.record_fetch(true, now);
+        self.last_fetch_at = Some(now);
+        self.schedule_auto_refresh(now);
+    }
+
+    /// Whether card actions wait for the pointer to hover the card. Touch screens have
+    /// nothing to hover with, so once a touch is seen (and always on the web, where a
+    /// touch can't be told from a click in time) they stay visible.
+    fn hover_actions(&mut self, ui: &Ui) -> bool {
+        self.touch_seen |= ui.input().any_touches();
+        self.config.actions_on_hover && !cfg!(target_arch = "wasm32") && !self.touch_seen
+    }
+
+    /// Whether `max_age_days` hides `a`, going by its date relative to `today`.
+    fn is_too_old(&self, a: &NewsCardData, today: DateTime<Utc>) -> bool {
+        if self.config.max_age_days == 0 {
+            return false;
+        }
+        let published = a.published_at.as_deref().map(DateTime::parse_from_rfc3339);
+        match published {
+            Some(Ok(published)) => {
+                today - published.with_timezone(&Utc)
+                    > Duration::days(self.config.max_age_days.into())
+            }
+            _ => !self.config.show_undated,
+        }
+    }
+
+    /// Runs newly fetched stories through the post-processor, then appends them, all
+    /// before the frame that first draws them.
+    pub(crate) fn add_articles(&mut self, mut articles: Vec<NewsCardData>) {
+        self.post_processor.post_process(&mut articles);
+        for article in articles {
+            self.add_article(article);
+        }
+    }
+
+    /// Appends a fetched story to the feed, unless it repeats one already there.
+    fn add_article(&mut self, mut news_data: NewsCardData) {
+        if self.config.hide_duplicates && self.is_duplicate(&news_data) {
+            tracing::debug!("hiding duplicate story {:?}", news_data.title);
+            return;
+        }
+        if !self.previous_ids.is_empty() && !self.previous_ids.contains(news_data.id()) {
+            self.new_articles.insert(news_data.id().to_string(), None);
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        self.notifier.observe(&news_data, &mut self.config.seen);
+        if self.config.compact && self.config.lazy_descriptions {
+            let texts = (news_data.description.take(), news_data.content.take());
+            self.deferred.defer(news_data.id(), texts);
+        }
+        if let Some(ids) = self.category_ids_mut() {
+            ids.push(news_data.id().to_string());
+        }
+        let pinned = self.config.pinned.iter().any(|id| id == news_data.id());
+        // a refresh keeps its new and already seen articles in two runs, in feed order
+        let new_first = self.config.new_articles_at == InsertOrder::Top;
+        let new_articles = &self.new_articles;
+        let second_run = |a: &NewsCardData| new_articles.contains_key(a.id()) != new_first;
+        let at = if second_run(&news_data) {
+            self.articles.len()
+        } else {
+            self.articles.iter().position(second_run).unwrap_or(self.articles.len())
+        };
+        self.articles.insert(at, news_data);
+        self.trim_feed();
+        // sources are mixed once the whole fetch is in, see `fetch_done`
+        if pinned {
+            self.move_pinned_to_front();
+        }
+    }
+
+    /// Drops the oldest articles until the feed fits `max_feed_length`, pinned and
+    /// bookmarked ones are always kept. Articles without a publish time count as the oldest.
+    fn trim_feed(&mut self) {
+        let max = self.config.max_feed_length;
+        if max == 0 || self.articles.len() <= max {
+            return;
+        }
+        let mut by_age: Vec<usize> = (0..self.articles.len())
+            .filter(|&i| {
+                let id = self.articles[i].id();
+                !self.config.pinned.iter().any(|p| p == id) && !self.is_bookmarked(id)
+            })
+            .collect();
+        by_age.sort_by_key(|&i| self.articles[i].published_at.as_deref());
+        let excess = self.articles.len() - max;
+        let mut drop = vec![false; self.articles.len()];
+        for &i in by_age.iter().take(excess) {
+            drop[i] = true;
+        }
+        let mut drop = drop.into_iter();
+        self.articles.retain(|_| !drop.next().unwrap_or(false));
+        let kept: HashSet<&str> = self.articles.iter().map(NewsCardData::id).collect();
+        self.new_articles.retain(|id, _| kept.contains(id.as_str()));
+    }
+
+    /// Where the feed's ids go while it shows the selected category's top headlines,
+    /// `None` for searches and the /everything home feed.
+    fn category_ids_mut(&mut self) -> Option<&mut Vec<String>> {
+        if self.searching || self.config.home_feed != HomeFeed::TopHeadlines || self.is_blended()
+        {
+            return None;
+        }
+        let category = self.config.category;
+        let entries = &mut self.config.category_ids;
+        let i = match entries.iter().position(|(c, _)| *c == category) {
+            Some(i) => i,
+            None => {
+                entries.push((category, Vec::new()));
+                entries.len() - 1
+            }
+        };
+        Some(&mut entries[i].1)
+    }
+
+    /// Headlines last fetched for each category that haven't been read yet.
+    fn unread_counts(&self) -> Vec<(Category, usize)> {
+        let read: HashSet<&str> = self.config.read.iter().map(String::as_str).collect();
+        self.config
+            .category_ids
+            .iter()
+            .map(|(c, ids)| (*c, ids.iter().filter(|id| !read.contains(id.as_str())).count()))
+            .collect()
+    }
+
+    /// Whether the feed already shows a story with a near-identical title.
+    fn is_duplicate(&self, article: &NewsCardData) -> bool {
+        let title = normalize_title(&article.title);
+        self.articles
+            .iter()
+            .any(|a| similar_titles(&normalize_title(&a.title), &title))
+    }
+
+    pub fn render_settings(&mut self, ctx: &Context) {
+        let mut open = self.show_settings;
+        Window::new(t("settings")).id(Id::new("settings")).open(&mut open).show(ctx, |ui| {
+            ComboBox::from_label(t("language"))
+                .selected_text(self.config.language.name())
+                .show_ui(ui, |ui| {
+                    for language in Language::ALL {
+                        ui.selectable_value(&mut self.config.language, language, language.name());
+                    }
+                });
+            ComboBox::from_label(t("icon_set"))
+                .selected_text(self.config.icon_set.name())
+                .show_ui(ui, |ui| {
+                    for set in IconSet::ALL {
+                        ui.selectable_value(&mut self.config.icon_set, set, set.name());
+                    }
+                });
+            ComboBox::from_label(t("number_format"))
+                .selected_text(self.config.number_format.name())
+                .show_ui(ui, |ui| {
+                    for format in NumberFormat::ALL {
+                        ui.selectable_value(&mut self.config.number_format, format, format.name());
+                    }
+                });
+            let mut country = self.config.country.unwrap_or_default();
+            ComboBox::from_label(t("country"))
+                .selected_text(country.name())
+                .show_ui(ui, |ui| {
+                    for c in Country::ALL {
+                        ui.selectable_value(&mut country, c, c.name());
+                    }
+                });
+            self.set_country(country);
+            ui.checkbox(&mut self.config.dark_mode, t("dark_mode"));
+            ui.horizontal(|ui| {
+                ui.color_edit_button_srgba(&mut self.config.accent_color);
+                ui.label(t("accent_color"));
+                let is_default = self.config.accent_color == DEFAULT_ACCENT;
+                if ui.add_enabled(!is_default, Button::new(t("reset_default"))).clicked() {
+                    self.config.accent_color = DEFAULT_ACCENT;
+                }
+            });
+            ui.checkbox(&mut self.config.compact, t("compact_view"));
+            self.render_line_styles(ui);
+            ComboBox::from_label(t("click_action"))
+                .selected_text(self.config.click_action.name())
+                .show_ui(ui, |ui| {
+                    for action in ClickAction::ALL {
+                        if action == ClickAction::Reader && !self.has_reader() {
+                            continue;
+                        }
+                        ui.selectable_value(&mut self.config.click_action, action, action.name());
+                    }
+                });
+            ComboBox::from_label(t("time_format"))
+                .selected_text(self.config.time_format.name())
+                .show_ui(ui, |ui| {
+                    for format in TimeFormat::ALL {
+                        ui.selectable_value(&mut self.config.time_format, format, format.name());
+                    }
+                });
+            ComboBox::from_label(t("new_articles_at"))
+                .selected_text(self.config.new_articles_at.name())
+                .show_ui(ui, |ui| {
+                    for order in InsertOrder::ALL {
+                        ui.selectable_value(&mut self.config.new_articles_at, order, order.name());
+                    }
+                });
+            ComboBox::from_label(t("density"))
+                .selected_text(self.config.density.name())
+                .show_ui(ui, |ui| {
+                    for density in Density::ALL {
+                        ui.selectable_value(&mut self.config.density, density, density.name());
+                    }
+                });
+            ui.checkbox(
+                &mut self.config.lazy_descriptions,
+                t("lazy_descriptions"),
+            );
+            ui.checkbox(&mut self.config.translate_titles, t("show_translate"));
+            ui.checkbox(&mut self.config.hide_duplicates, t("hide_duplicates"))
+                .on_hover_text(t("hide_duplicates_hint"));
+            ui.horizontal(|ui| {
+                ui.label(t("watchlist"));
+                let watchlist = TextEdit::singleline(&mut self.config.watchlist)
+                    .hint_text(t("watchlist_example"));
+                ui.add(watchlist).on_hover_text(t("watchlist_hint"));
+            });
+            ui.checkbox(&mut self.config.collapse_read, t("collapse_read"))
+                .on_hover_text(t("collapse_read_hint"));
+            ui.checkbox(&mut self.config.strip_source_suffix, t("strip_source_suffix"))
+                .on_hover_text(t("strip_source_suffix_hint"));
+            ui.checkbox(&mut self.config.source_tags, t("source_tags"))
+                .on_hover_text(t("source_tags_hint"));
+            if !cfg!(target_arch = "wasm32") {
+                ui.checkbox(&mut self.config.actions_on_hover, t("actions_on_hover"))
+                    .on_hover_text(t("actions_on_hover_hint"));
+            }
+            if !cfg!(target_arch = "wasm32") {
+                ui.checkbox(
+                    &mut self.config.notify_new_headlines,
+                    t("notify_new"),
+                );
+            }
+            #[cfg(not(target_arch = "wasm32"))]
+            {
+                let mut ticker = self.window.ticker;
+                if ui.checkbox(&mut ticker, t("ticker_mode")).changed() {
+                    self.set_ticker_mode(ticker);
+                }
+                let on_top = ui.checkbox(&mut self.window.always_on_top, t("always_on_top"));
+                if on_top.on_hover_text(t("after_restart")).changed() {
+                    self.window.save();
+                }
+                let mut insecure = self.config.danger_accept_invalid_certs;
+                let label = RichText::new(t("accept_invalid_certs")).color(RED);
+                let checkbox = ui.checkbox(&mut insecure, label);
+                if checkbox.on_hover_text(t("accept_invalid_certs_hint")).changed() {
+                    if insecure {
+                        self.pending_action = Some(SettingsAction::AcceptInvalidCerts);
+                    } else {
+                        self.set_accept_invalid_certs(false);
+                    }
+                }
+            }
+
+            ui.horizontal(|ui| {
+                ui.label(t("truncate_after"));
+                let max_chars = DragValue::new(&mut self.config.description_max_chars);
+                ui.add(max_chars.clamp_range(0..=2000));
+                ui.label(t("truncate_chars"));
+            });
+
+            ui.horizontal(|ui| {
+                ui.label(t("skip_within"));
+                let interval = DragValue::new(&mut self.config.min_refresh_interval);
+                ui.add(interval.clamp_range(0.0..=3600.0));
+                ui.label(t("skip_seconds"));
+            });
+
+            ui.horizontal(|ui| {
+                ui.label(t("auto_refresh_every"));
+                let mins = DragValue::new(&mut self.config.auto_refresh_mins);
+                if ui.add(mins.clamp_range(0..=1440)).changed() {
+                    self.schedule_auto_refresh(ui.input().time);
+                }
+                ui.label(t("auto_refresh_mins"));
+            });
+
+            ui.horizontal(|ui| {
+                ui.label(t("pull_to_refresh"));
+                let distance = DragValue::new(&mut self.config.pull_refresh_distance);
+                ui.add(distance.clamp_range(0.0..=600.0).speed(5.0));
+                ui.label(t("pull_to_refresh_unit"));
+            });
+
+            ui.horizontal(|ui| {
+                ui.label(t("max_run_per_source"));
+                let max_run = DragValue::new(&mut self.config.max_run_per_source);
+                if ui.add(max_run.clamp_range(0..=20)).changed() {
+                    self.diversify_sources();
+                }
+                ui.label(t("max_run_per_source_unit"));
+            });
+
+            ui.horizontal(|ui| {
+                ui.label(t("keep_bookmarks"));
+                let max = DragValue::new(&mut self.config.max_bookmarks);
+                if ui.add(max.clamp_range(0..=100_000)).changed() {
+                    self.evict_bookmarks();
+                }
+                ui.label(t("keep_bookmarks_unit"));
+            });
+
+            ui.horizontal(|ui| {
+                ui.label(t("keep_articles"));
+                let max = DragValue::new(&mut self.config.max_feed_length);
+                if ui.add(max.clamp_range(0..=100_000)).changed() {
+                    self.trim_feed();
+                }
+                ui.label(t("keep_articles_unit"));
+            });
+
+            ui.horizontal(|ui| {
+                ui.label(t("max_age"));
+                let days = DragValue::new(&mut self.config.max_age_days);
+                ui.add(days.clamp_range(0..=365));
+                ui.label(t("max_age_unit"));
+            });
+            ui.add_enabled_ui(self.config.max_age_days > 0, |ui| {
+                ui.indent("show_undated", |ui| {
+                    ui.checkbox(&mut self.config.show_undated, t("show_undated"))
+                        .on_hover_text(t("show_undated_hint"));
+                });
+            });
+
+            ui.horizontal(|ui| {
+                ui.label(t("image_downloads"));
+                let max = DragValue::new(&mut self.config.max_image_downloads);
+                ui.add(max.clamp_range(1..=32));
+                ui.label(t("image_downloads_unit"));
+            });
+
+            ui.checkbox(&mut self.config.pause_when_idle, t("pause_when_idle"))
+                .on_hover_text(t("pause_when_idle_hint"));
+
+            ui.horizontal(|ui| {
+                ui.label(t("buffer_up_to"));
+                let capacity = DragValue::new(&mut self.config.news_channel_capacity);
+                ui.add(capacity.clamp_range(1..=1000));
+                ui.label(t("buffer_articles"));
+            });
+
+            if cfg!(target_arch = "wasm32") {
+                ui.horizontal(|ui| {
+                    ui.label(t("wait"));
+                    let delay = DragValue::new(&mut self.config.startup_fetch_delay_ms);
+                    ui.add(delay.clamp_range(0..=5000));
+                    ui.label(t("startup_delay"));
+                });
+            }
+
+            ComboBox::from_label(t("citation_style"))
+                .selected_text(self.config.citation_style.name())
+                .show_ui(ui, |ui| {
+                    for style in CitationStyle::ALL {
+                        ui.selectable_value(&mut self.config.citation_style, style, style.name());
+                    }
+                });
+
+            let mut source = self.config.source;
+            ui.horizontal(|ui| {
+                self.health_dot(ui, source);
+                ComboBox::from_label(t("news_source"))
+                    .selected_text(source.name())
+                    .show_ui(ui, |ui| {
+                        for kind in SourceKind::ALL {
+                            ui.horizontal(|ui| {
+                                self.health_dot(ui, kind);
+                                ui.selectable_value(&mut source, kind, kind.name());
+                            });
+                        }
+                    });
+                if source == SourceKind::Rss {
+                    let url = TextEdit::singleline(&mut self.config.rss_url)
+                        .hint_text(t("rss_url"))
+                        .desired_width(200.0);
+                    if ui.add(url).lost_focus() {
+                        self.send_rss_url();
+                    }
+                }
+            });
+            self.set_source(source);
+
+            ui.horizontal(|ui| {
+                ui.label(t("source_filter"));
+                self.render_source_filter(ui);
+            });
+
+            self.render_blend_settings(ui);
+
+            let mut home_feed = self.config.home_feed;
+            ui.horizontal(|ui| {
+                ComboBox::from_label(t("home_feed"))
+                    .selected_text(home_feed.name())
+                    .show_ui(ui, |ui| {
+                        for feed in HomeFeed::ALL {
+                            ui.selectable_value(&mut home_feed, feed, feed.name());
+                        }
+                    });
+                if home_feed == HomeFeed::Everything {
+                    let topics = TextEdit::singleline(&mut self.config.home_query)
+                        .hint_text(t("home_query"))
+                        .desired_width(120.0);
+                    if ui.add(topics).lost_focus() {
+                        self.send_home_feed();
+                    }
+                }
+            });
+            if home_feed != self.config.home_feed {
+                self.config.home_feed = home_feed;
+                self.send_home_feed();
+            }
+
+            ComboBox::from_label(t("startup_show"))
+                .selected_text(match self.config.startup_view {
+                    StartupView::LastCategory => t("last_category").to_string(),
+                    StartupView::Category(c) => c.name().to_string(),
+                    StartupView::LastSearch => t("last_search").to_string(),
+                })
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(
+                        &mut self.config.startup_view,
+                        StartupView::LastCategory,
+                        t("last_category"),
+                    );
+                    ui.selectable_value(
+                        &mut self.config.startup_view,
+                        StartupView::LastSearch,
+                        t("last_search"),
+                    )
+                    .on_hover_text(t("last_search_hint"));
+                    for category in Category::ALL {
+                        ui.selectable_value(
+                            &mut self.config.startup_view,
+                            StartupView::Category(category),
+                            category.name(),
+                        );
+                    }
+                });
+
+            self.render_saved_searches_settings(ui);
+
+            ui.checkbox(&mut self.config.debug_overlay, t("debug_overlay"));
+
+            ui.horizontal(|ui| {
+                if ui.button(t("copy_diagnostics")).clicked() {
+                    ui.output().copied_text = self.diagnostics();
+                }
+                if ui.button(t("reading_stats")).clicked() {
+                    self.show_stats = !self.show_stats;
+                }
+            });
+            self.render_connection_test(ui);
+            self.render_storage_location(ui);
+
+            ui.add(Separator::default());
+            match self.pending_action {
+                None => {
+                    ui.horizontal(|ui| {
+                        if ui.button(t("reset_settings")).clicked() {
+                            self.pending_action = Some(SettingsAction::ResetSettings);
+                        }
+                        if ui.button(t("clear_cache")).clicked() {
+                            self.pending_action = Some(SettingsAction::ClearCache);
+                        }
+                    });
+                }
+                Some(action) => {
+                    ui.label(match action {
+                        SettingsAction::ResetSettings => {
+                            t("confirm_reset")
+                        }
+                        SettingsAction::ClearCache => t("confirm_clear"),
+                        #[cfg(not(target_arch = "wasm32"))]
+                        SettingsAction::AcceptInvalidCerts => t("confirm_invalid_certs"),
+                    });
+                    ui.horizontal(|ui| {
+                        if ui.button(t("yes")).clicked() {
+                            self.apply_settings_action(action);
+                            self.pending_action = None;
+                        }
+                        if ui.button(t("no")).clicked() {
+                            self.pending_action = None;
+                        }
+                    });
+                }
+            }
+        });
+        self.show_settings = open && self.show_settings;
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn set_accept_invalid_certs(&mut self, accept: bool) {
+        self.config.danger_accept_invalid_certs = accept;
+        self.update_http_agent();
+        self.send(Msg::AcceptInvalidCerts(accept));
+    }
+
+    /// Builds the agent for images and the reader again, with the current TLS setting.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn update_http_agent(&mut self) {
+        self.http = http_agent(self.config.danger_accept_invalid_certs);
+        self.images.set_agent(self.http.clone());
+    }
+
+    /// Stays up for as long as certificate checks are off, so it's never forgotten.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn render_tls_warning(&self, ctx: &Context) {
+        if !self.config.danger_accept_invalid_certs {
+            return;
+        }
+        TopBottomPanel::top("tls_warning").show(ctx, |ui| {
+            ui.vertical_centered(|ui| {
+                ui.colored_label(RED, plain(t("invalid_certs_banner")));
+            });
+        });
+    }
+
+    /// Plain-text summary of the app state for bug reports. Never includes the API key.
+    pub fn diagnostics(&self) -> String {
+        let api_key = if self.config.api_key.is_empty() {
+            "not set"
+        } else {
+            "set (redacted)"
+        };
+        format!(
+            "headlines {}\n\
+             target: {}-{}\n\
+             category: {}\n\
+             compact: {}\n\
+             api key: {}\n\
+             articles loaded: {}\n\
+             requests: {} succeeded, {} failed\n\
+             last error: {}\n\
+             persistent storage: {}\n\
+             deferred descriptions: {} bytes",
+            env!("CARGO_PKG_VERSION"),
+            std::env::consts::ARCH,
+            std::env::consts::OS,
+            self.config.category,
+            self.config.compact,
+            api_key,
+            self.articles.len(),
+            self.requests_succeeded,
+            self.requests_failed,
+            self.last_error.as_deref().unwrap_or("none"),
+            self.persistent,
+            self.deferred.size(),
+        )
+    }
+
+    fn apply_settings_action(&mut self, action: SettingsAction) {
+        match action {
+            SettingsAction::ResetSettings => {
+                // the stored config is overwritten with these defaults on the next save
+                self.config = HeadlinesConfig::default();
+                self.config.country = Some(detect_country().unwrap_or_default());
+                self.api_key_initialized = false;
+                self.show_settings = false;
+                self.search.clear();
+                self.searching = false;
+                self.clear_cache();
+                #[cfg(not(target_arch = "wasm32"))]
+                self.update_http_agent();
+                self.sources_list.applied = None;
+                self.send(Msg::Reset {
+                    query: Box::new(self.feed_query()),
+                    source: self.config.source,
+                });
+            }
+            SettingsAction::ClearCache => {
+                self.clear_cache();
+                self.send(Msg::Refresh);
+            }
+            #[cfg(not(target_arch = "wasm32"))]
+            SettingsAction::AcceptInvalidCerts => self.set_accept_invalid_certs(true),
+        }
+    }
+
+    fn clear_cache(&mut self) {
+        self.clear_feed();
+        self.previous_ids.clear();
+        self.translations.clear();
+        self.images.clear();
+        self.config.seen.clear();
+        #[cfg(not(target_arch = "wasm32"))]
+        self.notifier.reset();
+    }
+
+    /// Thin bar that scrolls every loaded headline from right to left.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn render_ticker(&mut self, ctx: &Context) {
+        use eframe::egui::{FontId, Pos2};
+
+        CentralPanel::default().show(ctx, |ui| {
+            ui.horizontal_centered(|ui| {
+                if icon_button(ui, "⤢", t("exit_ticker")).clicked() {
+                    self.set_ticker_mode(false);
+                }
+                let text = if self.articles.is_empty() {
+                    plain(t("loading"))
+                } else {
+                    let titles: Vec<&str> =
+                        self.articles.iter().map(|a| a.title.as_str()).collect();
+                    titles.join("   •   ")
+                };
+                let color = if self.config.dark_mode { WHITE } else { BLACK };
+                let (rect, _) = ui.allocate_exact_size(ui.available_size(), Sense::hover());
+                let font = FontId::proportional(14.0);
+                let galley = ui.painter().layout_no_wrap(text, font, color);
+                let span = galley.size().x + rect.width();
+                let offset = (ui.input().time as f32 * TICKER_SPEED) % span;
+                let pos = Pos2::new(rect.right() - offset, rect.center().y - galley.size().y / 2.0);
+                ui.painter().with_clip_rect(rect).galley(pos, galley);
+            });
+        });
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn set_ticker_mode(&mut self, ticker: bool) {
+        self.window.ticker = ticker;
+        self.window.save();
+        self.show_settings = false;
+        self.pending_window_size = Some(if ticker { TICKER_WINDOW_SIZE } else { WINDOW_SIZE });
+    }
+
+    /// Resizes the window after switching ticker mode. Shrinking is limited by the
+    /// minimum size the window was created with, so the bar is only fully thin after
+    /// a restart in ticker mode.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn apply_pending_window_size(&mut self, frame: &mut eframe::Frame) {
+        if let Some(size) = self.pending_window_size.take() {
+            frame.set_window_size(size);
+        }
+    }
+
+    /// Names what's on screen and how many of its articles are unread in the title, e.g.
+    /// "Headlines — Technology (5 new)", for the taskbar and window switcher.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn update_window_title(&mut self, frame: &mut eframe::Frame) {
+        let context = match self.view {
+            View::Bookmarks => t("bookmarks").to_string(),
+            View::ReadLater => t("read_later_queue").to_string(),
+            View::Feed | View::Digest if self.searching => format!("\"{}\"", self.search.trim()),
+            View::Feed | View::Digest => match self.config.home_feed {
+                HomeFeed::TopHeadlines => self.config.category.name().to_string(),
+                HomeFeed::Everything => t("scope_everything").to_string(),
+            },
+        };
+        let read = &self.config.read;
+        let key = (context, self.articles.len(), read.len(), read.last().cloned());
+        if self.window_title_key.as_ref() == Some(&key) {
+            return;
+        }
+        let read: HashSet<&str> = read.iter().map(String::as_str).collect();
+        let unread = self.articles.iter().filter(|a| !read.contains(a.id())).count();
+        let title = if unread > 0 {
+            tf("window_title_unread", &[&key.0, &unread])
+        } else {
+            tf("window_title", &[&key.0])
+        };
+        frame.set_window_title(&title);
+        self.window_title_key = Some(key);
+    }
+
+    pub fn render_config(&mut self, ctx: &Context) {
+        CentralPanel::default().show(ctx, |_ui| {
+            Window::new(t("configuration")).id(Id::new("configuration")).show(ctx, |ui| {
+                ui.label(t("enter_api_key"));
+                let text_input = ui.text_edit_singleline(&mut self.config.api_key);
+                if text_input.changed() {
+                    self.api_key_missing = false;
+                }
+                if text_input.lost_focus() && ui.input().key_pressed(Key::Enter) {
+                    let key = self.config.api_key.trim().to_string();
+                    if key.is_empty() {
+                        // keep the window up rather than loading forever without a key
+                        self.api_key_missing = true;
+                        text_input.request_focus();
+                    } else {
+                        self.config.api_key = key;
+                        self.api_key_initialized = true;
+                        self.api_key_rejected = false;
+                        self.clear_feed();
+                        self.send(Msg::ApiKeySet(self.config.api_key.to_string()));
+                    }
+                }
+                if self.api_key_missing {
+                    ui.colored_label(RED, t("api_key_required"));
+                } else if self.api_key_rejected {
+                    ui.colored_label(RED, t("api_key_rejected"));
+                }
+                self.render_connection_test(ui);
+                ui.label(t("register_api_key"));
+                ui.hyperlink("https://newsapi.org");
+                if !self.persistent {
+                    ui.colored_label(
+                        RED,
+                        t("storage_unavailable_key"),
+                    );
+                }
+            });
+        });
+    }
+}
+
+/// `text` laid out like a label, with the `matches` byte ranges marked like a highlighter.
+fn highlighted(
+    ui: &Ui,
+    text: &str,
+    matches: &[Range<usize>],
+    color: Color32,
+    line: &LineStyle,
+) -> LayoutJob {
+    let font_id = TextStyle::Body.resolve(ui.style());
+    let plain = line.format(TextFormat::simple(font_id.clone(), color));
+    let marked = line.format(TextFormat {
+        background: AMBER,
+        ..TextFormat::simple(font_id, BLACK)
+    });
+    let mut job = LayoutJob::default();
+    let mut at = 0;
+    for m in matches {
+        job.append(&text[at..m.start], 0.0, plain.clone());
+        job.append(&text[m.clone()], 0.0, marked.clone());
+        at = m.end;
+    }
+    job.append(&text[at..], 0.0, plain);
+    job
+}
+
+/// Icon-only button with a tooltip and an accessible name for screen readers.
+fn icon_button(ui: &mut Ui, icon: &'static str, label: &str) -> Response {
+    let btn = ui
+        .add(Button::new(RichText::new(icons::icon(icon)).text_style(TextStyle::Body)))
+        .on_hover_text(label);
+    btn.widget_info(|| WidgetInfo::labeled(WidgetType::Button, label));
+    btn
+}
+
+/// Draws one of the toolbar's buttons from its icon and what it does.
+type ToolbarButton = fn(&mut Ui, &'static str, &str) -> Response;
+
+/// An entry of the toolbar's overflow menu, labelled since there's room for it there.
+fn menu_item(ui: &mut Ui, icon: &'static str, label: &str) -> Response {
+    ui.button(format!("{} {}", icons::icon(icon), label))
+}
+
+/// What the toolbar's buttons asked for, and where its two halves ended up.
+struct Toolbar {
+    quit: bool,
+    logo: Rect,
+    buttons: Rect,
+}
+
+/// Turns a finished fetch of `page` into the events the UI thread consumes, `failed`
+/// being the blended categories that didn't load.
+fn fetch_events(
+    page: u32,
+    result: Result<Fetched, NewsApiError>,
+    failed: Vec<Category>,
+) -> Vec<FetchEvent> {
+    match result {
+        Ok(fetched) if page > 1 => vec![FetchEvent::NextPage(fetched.articles)],
+        Ok(fetched) if fetched.not_modified => vec![FetchEvent::NotModified {
+            total: fetched.total_results,
+            articles: fetched.articles,
+        }],
+        Ok(fetched) => {
+            let mut events = vec![FetchEvent::Started {
+                total: fetched.total_results,
+            }];
+            if !failed.is_empty() {
+                events.push(FetchEvent::CategoriesFailed(failed));
+            }
+            let articles = fetched.articles.into_iter();
+            events.extend(articles.map(|news| FetchEvent::Article(Box::new(news))));
+            events.push(FetchEvent::Done);
+            events
+        }
+        Err(e) => {
+            tracing::error!("failed fetching news: {}", e);
+            vec![FetchEvent::Error(e)]
+        }
+    }
+}
+
+/// Sends the articles of a first page on as the source decodes them, so the feed starts
+/// filling in before a large response is read to the end. A blended feed has to wait
+/// for every category to merge them, and later pages go out as one event anyway.
+#[cfg(not(target_arch = "wasm32"))]
+fn stream_first_page(
+    source: &(dyn ArticleSource + Sync),
+    query: &Query,
+    send: impl Fn(FetchEvent),
+) {
+    let mut started = false;
+    let result = source.fetch_each(
+        query,
+        &mut |total| {
+            started = true;
+            send(FetchEvent::Started { total });
+        },
+        &mut |news| send(FetchEvent::Article(Box::new(news))),
+    );
+    match result {
+        Ok(_) if started => send(FetchEvent::Done),
+        result => fetch_events(query.page, result, Vec::new()).into_iter().for_each(send),
+    }
+}
+
+/// Sends on the bounded news channel. When the UI falls behind and the channel
+/// is full the worker waits for room, giving up on an `Article` after
+/// `SEND_TIMEOUT_MS` so a stalled UI can't pile up memory. Every other event moves
+/// the feed from one state to the next, so those are waited on for as long as it takes:
+/// without `Done` or `Error` the feed would stay loading, and without `Started` the
+/// articles after it would land in the old feed.
+#[cfg(not(target_arch = "wasm32"))]
+fn send_event(news_tx: &SyncSender<FetchEvent>, mut event: FetchEvent) {
+    let mut waited = 0;
+    loop {
+        match news_tx.try_send(event) {
+            Ok(()) => return,
+            Err(TrySendError::Full(e)) if waited < SEND_TIMEOUT_MS || !droppable(&e) => {
+                event = e;
+                thread::sleep(std::time::Duration::from_millis(SEND_RETRY_MS.into()));
+                waited += SEND_RETRY_MS;
+            }
+            Err(e) => {
+                tracing::error!("Error sending news data: {}", e);
+                return;
+            }
+        }
+    }
+}
+
+/// Same as the native version, but yields to the browser while waiting
+/// since the UI runs on the same thread.
+#[cfg(target_arch = "wasm32")]
+async fn send_event(news_tx: &SyncSender<FetchEvent>, mut event: FetchEvent) {
+    let mut waited = 0;
+    loop {
+        match news_tx.try_send(event) {
+            Ok(()) => return,
+            Err(TrySendError::Full(e)) if waited < SEND_TIMEOUT_MS || !droppable(&e) => {
+                event = e;
+                gloo_timers::future::TimeoutFuture::new(SEND_RETRY_MS).await;
+                waited += SEND_RETRY_MS;
+            }
+            Err(e) => {
+                tracing::error!("Error sending news data: {}", e);
+                return;
+            }
+        }
+    }
+}
+
+/// Whether `event` can be dropped when the UI doesn't keep up. A missing article only
+/// leaves a gap in the feed.
+fn droppable(event: &FetchEvent) -> bool {
+    matches!(event, FetchEvent::Article(_))
+}
+
+/// What the fetcher fetches, kept up to date by the messages the UI sends.
+struct FetchTarget {
+    query: Query,
+    api_key: String,
+    source_kind: SourceKind,
+    rss_url: String,
+    #[cfg(not(target_arch = "wasm32"))]
+    accept_invalid_certs: bool,
+}
+
+impl FetchTarget {
+    #[cfg(not(target_arch = "wasm32"))]
+    fn source(&self) -> BoxedSource {
+        self.source_kind.source(&self.api_key, &self.rss_url, self.accept_invalid_certs)
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn source(&self) -> BoxedSource {
+        self.source_kind.source(&self.api_key, &self.rss_url)
+    }
+
+    /// Applies `msg`, then starts the fetch it calls for, if any. Shared by the native
+    /// worker thread and the wasm dispatch so both handle messages the same way.
+    fn apply(&mut self, msg: Msg, fetcher: &mut Fetcher) {
+        match self.update(msg) {
+            Effect::None => {}
+            Effect::Fetch => fetcher.spawn(self.query.clone()),
+            Effect::FetchPage(query) => fetcher.spawn_page(query),
+            Effect::NewSource { fetch } => {
+                fetcher.set_source(self.source());
+                if fetch {
+                    fetcher.spawn(self.query.clone());
+                }
+            }
+        }
+    }
+
+    fn update(&mut self, msg: Msg) -> Effect {
+        match msg {
+            Msg::ApiKeySet(key) => {
+                self.api_key = key;
+                return Effect::NewSource { fetch: true };
+            }
+            Msg::Refresh => {}
+            Msg::CategoryChanged(category) => self.query.category = category,
+            Msg::SourceChanged(kind) => {
+                self.source_kind = kind;
+                return Effect::NewSource { fetch: true };
+            }
+            Msg::RssUrlChanged(url) => {
+                self.rss_url = url;
+                return Effect::NewSource { fetch: true };
+            }
+            #[cfg(not(target_arch = "wasm32"))]
+            Msg::AcceptInvalidCerts(accept) => {
+                self.accept_invalid_certs = accept;
+                return Effect::NewSource { fetch: true };
+            }
+            Msg::HomeFeedChanged(home) => self.query.home = home,
+            Msg::CountryChanged(country) => self.query.country = country,
+            Msg::LowBandwidth(low) => {
+                // takes effect with the next fetch
+                self.query.page_size = page_size(low);
+                return Effect::None;
+            }
+            Msg::BlendChanged(categories) => self.query.blend = categories,
+            Msg::SourceFilterChanged(sources) => self.query.sources = sources,
+            Msg::FetchPage(page) => {
+                let mut next = self.query.clone();
+                next.page = page;
+                return Effect::FetchPage(next);
+            }
+            Msg::Reset { query, source } => {
+                self.query = *query;
+                self.source_kind = source;
+                self.rss_url.clear();
+                #[cfg(not(target_arch = "wasm32"))]
+                {
+                    self.accept_invalid_certs = false;
+                }
+                return Effect::NewSource { fetch: false };
+            }
+            Msg::SearchChanged {
+                search,
+                scope,
+                category,
+            } => {
+                self.query.search = search;
+                self.query.scope = scope;
+                self.query.category = category;
+            }
+        }
+        Effect::Fetch
+    }
+}
+
+/// What the fetcher has to do once a message is applied.
+enum Effect {
+    None,
+    /// Fetch the first page of the query.
+    Fetch,
+    FetchPage(Query),
+    /// Build the source again, fetching with it if `fetch`.
+    NewSource { fetch: bool },
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) type Fetcher = NativeFetcher;
+#[cfg(target_arch = "wasm32")]
+pub(crate) type Fetcher = WebFetcher;
+
+/// Runs each native fetch on its own thread so the worker is free to start the
+/// next one right away. A blocking request can't be interrupted, so a superseded
+/// fetch is left to finish and its results are dropped instead of reaching the feed.
+/// Later pages count their own generation, so prefetching one never supersedes the
+/// first page while it's still streaming in.
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) struct NativeFetcher {
+    source: Arc<dyn ArticleSource + Send + Sync>,
+    news_tx: SyncSender<FetchEvent>,
+    generation: Arc<AtomicU64>,
+    page_generation: Arc<AtomicU64>,
+    repaint: Context,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl NativeFetcher {
+    pub(crate) fn new(
+        source: BoxedSource,
+        news_tx: SyncSender<FetchEvent>,
+        repaint: Context,
+    ) -> Self {
+        NativeFetcher {
+            source: source.into(),
+            news_tx,
+            generation: Arc::new(AtomicU64::new(0)),
+            page_generation: Arc::new(AtomicU64::new(0)),
+            repaint,
+        }
+    }
+
+    pub(crate) fn set_source(&mut self, source: BoxedSource) {
+        self.source = source.into();
+    }
+
+    /// Fetches the first page of `query`, superseding every fetch before it.
+    pub(crate) fn spawn(&mut self, query: Query) {
+        // pages of the previous query mustn't land in the new feed either
+        self.page_generation.fetch_add(1, Ordering::SeqCst);
+        let generation = self.generation.fetch_add(1, Ordering::SeqCst) + 1;
+        self.run(query, self.generation.clone(), generation);
+    }
+
+    /// Fetches a later page, superseding only the previous page fetch.
+    pub(crate) fn spawn_page(&mut self, query: Query) {
+        let generation = self.page_generation.fetch_add(1, Ordering::SeqCst) + 1;
+        self.run(query, self.page_generation.clone(), generation);
+    }
+
+    fn run(&self, mut query: Query, current: Arc<AtomicU64>, generation: u64) {
+        let source = self.source.clone();
+        let news_tx = self.news_tx.clone();
+        let repaint = self.repaint.clone();
+        let superseded = move || current.load(Ordering::SeqCst) != generation;
+        // the source skips its retries once a newer request is in
+        query.superseded = Some(Arc::new(superseded.clone()));
+        thread::spawn(move || {
+            let send = |event| {
+                // a newer request superseded this one, drop whatever is left
+                if superseded() {
+                    tracing::debug!("dropping results of a superseded fetch");
+                    return;
+                }
+                send_event(&news_tx, event);
+                repaint.request_repaint();
+            };
+            let queries = blend_queries(&query);
+            if query.page == 1 && queries.len() == 1 {
+                stream_first_page(&*source, &query, send);
+                return;
+            }
+            let mut results = Vec::new();
+            for query in queries {
+                if superseded() {
+                    tracing::debug!("skipping the rest of a superseded blend");
+                    return;
+                }
+                results.push((query.category, pollster::block_on(source.fetch_page(&query))));
+            }
+            let (result, failed) = merge_blend(results);
+            fetch_events(query.page, result, failed).into_iter().for_each(send);
+        });
+    }
+}
+
+/// Runs source fetches on wasm, aborting the previous request whenever a
+/// new one starts so only the latest query ever updates the feed. Later pages have
+/// their own request and generation, like on native.
+#[cfg(target_arch = "wasm32")]
+pub(crate) struct WebFetcher {
+    source: Rc<dyn ArticleSource>,
+    news_tx: SyncSender<FetchEvent>,
+    controller: Option<web_sys::AbortController>,
+    page_controller: Option<web_sys::AbortController>,
+    generation: Rc<Cell<u64>>,
+    page_generation: Rc<Cell<u64>>,
+    repaint: Context,
+}
+
+#[cfg(target_arch = "wasm32")]
+impl WebFetcher {
+    pub(crate) fn new(
+        source: BoxedSource,
+        news_tx: SyncSender<FetchEvent>,
+        repaint: Context,
+    ) -> Self {
+        WebFetcher {
+            source: source.into(),
+            news_tx,
+            controller: None,
+            page_controller: None,
+            generation: Rc::new(Cell::new(0)),
+            page_generation: Rc::new(Cell::new(0)),
+            repaint,
+        }
+    }
+
+    pub(crate) fn set_source(&mut self, source: BoxedSource) {
+        self.source = source.into();
+    }
+
+    /// Fetches the first page of `query`, aborting every fetch before it.
+    pub(crate) fn spawn(&mut self, mut query: Query) {
+        if let Some(controller) = self.page_controller.take() {
+            controller.abort();
+        }
+        self.page_generation.set(self.page_generation.get() + 1);
+        query.signal = restart(&mut self.controller);
+        let generation = self.generation.get() + 1;
+        self.generation.set(generation);
+        self.run(query, self.generation.clone(), generation);
+    }
+
+    /// Fetches a later page, aborting only the previous page fetch.
+    pub(crate) fn spawn_page(&mut self, mut query: Query) {
+        query.signal = restart(&mut self.page_controller);
+        let generation = self.page_generation.get() + 1;
+        self.page_generation.set(generation);
+        self.run(query, self.page_generation.clone(), generation);
+    }
+
+    fn run(&self, query: Query, current: Rc<Cell<u64>>, generation: u64) {
+        let source = self.source.clone();
+        let news_tx = self.news_tx.clone();
+        let repaint = self.repaint.clone();
+        wasm_bindgen_futures::spawn_local(async move {
+            let mut results = Vec::new();
+            for query in blend_queries(&query) {
+                let result = source.fetch_page(&query).await;
+                results.push((query.category, result));
+            }
+            // a newer request superseded this one, drop whatever it returned
+            if current.get() != generation {
+                return;
+            }
+            let (result, failed) = merge_blend(results);
+            for event in fetch_events(query.page, result, failed) {
+                send_event(&news_tx, event).await;
+                repaint.request_repaint();
+            }
+        });
+    }
+}
+
+/// Aborts the request `controller` belongs to and gives the next one a fresh signal.
+#[cfg(target_arch = "wasm32")]
+fn restart(controller: &mut Option<web_sys::AbortController>) -> Option<web_sys::AbortSignal> {
+    if let Some(controller) = controller.take() {
+        controller.abort();
+    }
+    *controller = web_sys::AbortController::new().ok();
+    controller.as_ref().map(|c| c.signal())
+}
+
+/// Where the config is stored: [`STORAGE_KEY`], or `headlines-{profile}` for a profile
+/// named in [`PROFILE_ENV`], so profiles keep their own settings and bookmarks.
+#[cfg(not(target_arch = "wasm32"))]
+fn storage_key() -> String {
+    match std::env::var(PROFILE_ENV) {
+        Ok(profile) if !profile.trim().is_empty() => {
+            format!("{}-{}", STORAGE_KEY, profile.trim())
+        }
+        _ => STORAGE_KEY.to_string(),
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+fn storage_key() -> String {
+    STORAGE_KEY.to_string()
+}
+
+/// The feed as `N. Title — URL` lines, for pasting where markup would get in the way.
+fn feed_as_text(articles: &[NewsCardData]) -> String {
+    articles
+        .iter()
+        .enumerate()
+        .map(|(i, a)| format!("{}. {} — {}\n", i + 1, a.title, a.url))
+        .collect()
+}
+
+/// Makes MesloLGS the first proportional font. Should the proportional family ever be
+/// missing, the definitions are returned without it rather than panicking.
+fn with_meslo(mut font_def: FontDefinitions) -> FontDefinitions {
+    font_def.font_data.insert(
+        "MesloLGS".to_string(),
+        FontData::from_static(include_bytes!("../../MesloLGS_NF_Regular.ttf")),
+    );
+    match font_def.families.get_mut(&FontFamily::Proportional) {
+        Some(family) => family.insert(0, "MesloLGS".to_string()),
+        None => tracing::warn!("no proportional font family, keeping the default fonts"),
+    }
+    font_def
+}
+
+/// Random number in `[0, 1)`.
+#[cfg(not(target_arch = "wasm32"))]
+fn random_unit() -> f64 {
+    rand::random()
+}
+
+#[cfg(target_arch = "wasm32")]
+fn random_unit() -> f64 {
+    js_sys::Math::random()
+}
+
+/// Default for how many articles the feed holds before the oldest are trimmed.
+pub const MAX_FEED_LENGTH: usize = 500;
+
+/// Articles per page while the data saver is on.
+pub const LOW_BANDWIDTH_PAGE_SIZE: u32 = 10;
+
+pub(crate) fn page_size(low_bandwidth: bool) -> Option<u32> {
+    low_bandwidth.then_some(LOW_BANDWIDTH_PAGE_SIZE)
+}
+
+/// How long before `now` something was `published`, e.g. "3h ago".
+fn relative_time(published: DateTime<Utc>, now: DateTime<Utc>) -> String {
+    let minutes = (now - published).num_minutes().max(0);
+    match minutes {
+        0 => t("time_just_now").to_string(),
+        1..=59 => tf("time_minutes_ago", &[&minutes]),
+        60..=1439 => tf("time_hours_ago", &[&(minutes / 60)]),
+        _ => tf("time_days_ago", &[&(minutes / 1440)]),
+    }
+}
+
+/// `published` as `YYYY-MM-DD HH:MM`, in UTC as that's what newsapi reports.
+fn absolute_time(published: DateTime<Utc>) -> String {
+    published.format("%Y-%m-%d %H:%M UTC").to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io,
+        sync::{Arc, Mutex},
+    };
+
+    use eframe::egui::{Pos2, RawInput};
+
+    use super::*;
+
+    /// Log output, shared with the subscriber writing it.
+    #[derive(Clone, Default)]
+    struct Captured(Arc<Mutex<Vec<u8>>>);
+
+    impl io::Write for Captured {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    /// Runs `f` and returns what it logged.
+    fn logs(f: impl FnOnce()) -> String {
+        let captured = Captured::default();
+        let writer = captured.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .with_ansi(false)
+            .with_writer(move || writer.clone())
+            .finish();
+        tracing::subscriber::with_default(subscriber, f);
+        let bytes = captured.0.lock().unwrap().clone();
+        String::from_utf8(bytes).unwrap()
+    }
+
+    /// Serves the articles it was given, or fails when it has none, so the feed can be
+    /// tested without a network.
+    struct FakeSource(Option<Vec<&'static str>>);
+
+    #[async_trait::async_trait]
+    impl ArticleSource for FakeSource {
+        async fn fetch(&self, _query: &Query) -> Result<Vec<NewsCardData>, NewsApiError> {
+            let titles = self.0.as_ref().ok_or(NewsApiError::RateLimited)?;
+            let card = |title: &&str| {
+                let mut card = NewsCardData::new(title, &format!("https://example.com/{}", title));
+                card.source = "Fake".to_string();
+                card
+            };
+            Ok(titles.iter().map(card).collect())
+        }
+    }
+
+    /// Loads `source` into `headlines` the way a fetch from the UI would, waiting for
+    /// the feed to be done loading. `seen` gets the titles on screen after every event.
+    fn load(headlines: &mut Headlines, source: FakeSource, mut seen: impl FnMut(Vec<String>)) {
+        let ctx = Context::default();
+        let (news_tx, news_rx) = sync_channel(4);
+        headlines.news_rx = Some(news_rx);
+        let mut fetcher = NativeFetcher::new(Box::new(source), news_tx, ctx.clone());
+        fetcher.spawn(Query::new(Category::General));
+        let started = std::time::Instant::now();
+        while headlines.fetch_state == FetchState::Loading {
+            assert!(started.elapsed().as_secs() < 5, "the feed never finished loading");
+            if headlines.preload_articles(&ctx) {
+                seen(headlines.articles.iter().map(|a| a.title.clone()).collect());
+            } else {
+                thread::sleep(std::time::Duration::from_millis(1));
+            }
+        }
+    }
+
+    #[test]
+    fn the_feed_fills_from_its_source() {
+        let mut headlines = Headlines::new();
+        let mut shown = Vec::new();
+        let source = FakeSource(Some(vec!["One", "Two", "Three"]));
+        load(&mut headlines, source, |titles| shown.push(titles.len()));
+        let titles: Vec<&str> = headlines.articles.iter().map(|a| a.title.as_str()).collect();
+        assert_eq!(titles, ["One", "Two", "Three"]);
+        assert_eq!(headlines.total_results, Some(3));
+        assert_eq!(headlines.fetch_state, FetchState::Done);
+        // Started, one event per article and Done
+        assert_eq!(shown, [0, 1, 2, 3, 3]);
+    }
+
+    #[test]
+    fn a_failing_source_leaves_an_error() {
+        let mut headlines = Headlines::new();
+        load(&mut headlines, FakeSource(None), |_| {});
+        assert!(headlines.articles.is_empty());
+        let error = NewsApiError::RateLimited.to_string();
+        assert_eq!(headlines.fetch_state, FetchState::Failed(error.clone()));
+        assert_eq!(headlines.last_error, Some(error));
+    }
+
+    /// Drops every article with "muted" in the title.
+    struct Mute;
+
+    impl PostProcess for Mute {
+        fn post_process(&self, articles: &mut Vec<NewsCardData>) {
+            articles.retain(|a| !a.title.contains("muted"));
+        }
+    }
+
+    #[test]
+    fn post_processing_runs_before_articles_are_shown() {
+        let mut headlines = Headlines::new().with_post_processor(Mute);
+        let source = FakeSource(Some(vec!["One", "muted two", "Three", "muted four"]));
+        load(&mut headlines, source, |titles| {
+            assert!(titles.iter().all(|t| !t.contains("muted")), "{:?}", titles);
+        });
+        assert_eq!(headlines.articles.len(), 2);
+    }
+
+    #[test]
+    fn resetting_settings_fetches_the_default_feed_once_a_key_is_set() {
+        let mut headlines = Headlines::new();
+        let (app_tx, app_rx) = sync_channel(4);
+        headlines.app_tx = Some(app_tx);
+        headlines.config.category = Category::Sports;
+        headlines.config.low_bandwidth = true;
+        headlines.search = "rust".to_string();
+        headlines.searching = true;
+        let mut target = FetchTarget {
+            query: headlines.feed_query(),
+            api_key: "old key".to_string(),
+            source_kind: SourceKind::Demo,
+            rss_url: "https://example.com/feed.xml".to_string(),
+            accept_invalid_certs: true,
+        };
+
+        headlines.apply_settings_action(SettingsAction::ResetSettings);
+        assert!(!headlines.searching);
+        assert!(headlines.config.country.is_some());
+        let reset = target.update(app_rx.try_recv().unwrap());
+        assert!(matches!(reset, Effect::NewSource { fetch: false }));
+        assert!(app_rx.try_recv().is_err());
+
+        let key_set = target.update(Msg::ApiKeySet("new key".to_string()));
+        assert!(matches!(key_set, Effect::NewSource { fetch: true }));
+        let defaults = Headlines::new();
+        assert_eq!(target.source_kind, defaults.config.source);
+        assert!(target.rss_url.is_empty());
+        assert!(!target.accept_invalid_certs);
+        assert_eq!(target.query.category, defaults.config.category);
+        assert_eq!(target.query.search, None);
+        assert_eq!(target.query.page_size, page_size(defaults.config.low_bandwidth));
+        assert_eq!(target.query.country, headlines.config.country.unwrap());
+    }
+
+    #[test]
+    fn only_articles_are_dropped_when_the_ui_falls_behind() {
+        let (news_tx, news_rx) = sync_channel(1);
+        news_tx.send(FetchEvent::Started { total: 2 }).unwrap();
+        let worker = thread::spawn(move || {
+            send_event(&news_tx, FetchEvent::Article(Box::new(NewsCardData::new("a", "b"))));
+            send_event(&news_tx, FetchEvent::Done);
+        });
+        let timeout = std::time::Duration::from_millis(SEND_TIMEOUT_MS.into());
+        // long enough for the article to be given up on, and for `Done` to time out too
+        // if it were treated the same
+        thread::sleep(timeout * 2 + std::time::Duration::from_millis(200));
+        assert!(matches!(news_rx.recv().unwrap(), FetchEvent::Started { total: 2 }));
+        assert!(matches!(news_rx.recv_timeout(timeout).unwrap(), FetchEvent::Done));
+        worker.join().unwrap();
+    }
+
+    /// Counts the articles it's handed.
+    struct Count(Arc<AtomicU64>);
+
+    impl PostProcess for Count {
+        fn post_process(&self, articles: &mut Vec<NewsCardData>) {
+            self.0.fetch_add(articles.len() as u64, Ordering::SeqCst);
+        }
+    }
+
+    #[test]
+    fn each_article_is_post_processed_once() {
+        let count = Arc::new(AtomicU64::new(0));
+        let mut headlines = Headlines::new().with_post_processor(Count(count.clone()));
+        let source = FakeSource(Some(vec!["One", "Two", "Three", "Four"]));
+        load(&mut headlines, source, |_| {});
+        assert_eq!(count.load(Ordering::SeqCst), 4);
+    }
+
+    /// Lays out the toolbar in a window `width` points wide.
+    fn toolbar_at(width: f32) -> Toolbar {
+        let mut headlines = Headlines::new();
+        // the widest the left half gets
+        headlines.config.low_bandwidth = true;
+        let ctx = Context::default();
+        headlines.configure_fonts(&ctx);
+        let screen = Rect::from_min_size(Pos2::ZERO, Vec2::new(width, 480.0));
+        let input = RawInput {
+            screen_rect: Some(screen),
+            ..Default::default()
+        };
+        let mut toolbar = None;
+        // the first frame measures how wide the toolbar is
+        for _ in 0..2 {
+            let _ = ctx.run(input.clone(), |ctx| {
+                TopBottomPanel::top("top_panel").show(ctx, |ui| {
+                    toolbar = Some(headlines.render_toolbar(ui));
+                });
+            });
+        }
+        toolbar.unwrap()
+    }
+
+    #[test]
+    fn the_toolbar_fits_the_minimum_window_width() {
+        for width in [crate::config_file::MIN_WINDOW_SIZE.x, 640.0, 1024.0] {
+            let toolbar = toolbar_at(width);
+            assert!(!toolbar.logo.intersects(toolbar.buttons), "overlap at {}", width);
+            assert!(toolbar.buttons.min.x >= 0.0, "{:?} at {}", toolbar.buttons, width);
+            assert!(toolbar.buttons.max.x <= width, "{:?} at {}", toolbar.buttons, width);
+        }
+    }
+
+    fn titles(headlines: &Headlines) -> Vec<&str> {
+        headlines.articles.iter().map(|a| a.title.as_str()).collect()
+    }
+
+    /// A feed of `(title, published_at)` cards.
+    fn dated(cards: &[(&str, Option<&str>)]) -> Vec<NewsCardData> {
+        let card = |(title, published_at): &(&str, Option<&str>)| {
+            let mut card = NewsCardData::new(title, &format!("https://example.com/{}", title));
+            card.published_at = published_at.map(str::to_string);
+            card
+        };
+        cards.iter().map(card).collect()
+    }
+
+    #[test]
+    fn trimming_drops_the_oldest_articles() {
+        let mut headlines = Headlines::new();
+        headlines.config.max_feed_length = 3;
+        headlines.articles = dated(&[
+            ("new", Some("2024-03-05T00:00:00Z")),
+            ("undated", None),
+            ("old", Some("2024-03-01T00:00:00Z")),
+            ("newer", Some("2024-03-06T00:00:00Z")),
+            ("middle", Some("2024-03-03T00:00:00Z")),
+        ]);
+        headlines.trim_feed();
+        assert_eq!(titles(&headlines), ["new", "newer", "middle"]);
+    }
+
+    #[test]
+    fn trimming_keeps_pinned_and_bookmarked_articles() {
+        let mut headlines = Headlines::new();
+        headlines.config.max_feed_length = 1;
+        headlines.articles = dated(&[
+            ("pinned", Some("2024-03-01T00:00:00Z")),
+            ("bookmarked", Some("2024-03-02T00:00:00Z")),
+            ("new", Some("2024-03-09T00:00:00Z")),
+        ]);
+        headlines.config.pinned.push(headlines.articles[0].id().to_string());
+        let bookmark = Bookmark::from(&headlines.articles[1]);
+        headlines.config.bookmarks.push(bookmark);
+        headlines.trim_feed();
+        // over the cap, since neither of them can go
+        assert_eq!(titles(&headlines), ["pinned", "bookmarked"]);
+    }
+
+    /// A feed of cards titled by their source, `"A2"` being the second from `A`.
+    fn sourced(titles: &[&str]) -> Vec<NewsCardData> {
+        let card = |title: &&str| {
+            let mut card = NewsCardData::new(title, &format!("https://example.com/{}", title));
+            card.source = title[..1].to_string();
+            card
+        };
+        titles.iter().map(card).collect()
+    }
+
+    #[test]
+    fn diversifying_breaks_up_long_runs_from_one_source() {
+        let mut headlines = Headlines::new();
+        headlines.config.max_run_per_source = 2;
+        headlines.articles = sourced(&["A1", "A2", "A3", "A4", "B1", "B2", "C1"]);
+        headlines.diversify_sources();
+        assert_eq!(titles(&headlines), ["A1", "A2", "B1", "A3", "A4", "B2", "C1"]);
+        for run in headlines.articles.windows(3) {
+            assert!(run.iter().any(|a| a.source != run[0].source), "{:?}", titles(&headlines));
+        }
+    }
+
+    #[test]
+    fn diversifying_keeps_the_order_where_it_cant_help() {
+        let mut headlines = Headlines::new();
+        headlines.config.max_run_per_source = 1;
+        headlines.articles = sourced(&["A1", "B1", "A2", "A3", "A4"]);
+        headlines.diversify_sources();
+        assert_eq!(titles(&headlines), ["A1", "B1", "A2", "A3", "A4"]);
+        headlines.config.max_run_per_source = 0;
+        headlines.articles = sourced(&["A1", "A2", "B1"]);
+        headlines.diversify_sources();
+        assert_eq!(titles(&headlines), ["A1", "A2", "B1"]);
+    }
+
+    #[test]
+    fn a_card_with_a_malformed_url_has_no_link() {
+        let json = r#"{"title": "Broken", "url": "htp:/broken link", "source": {"name": "S"}}"#;
+        let article: Article = serde_json::from_str(json).unwrap();
+        let card = NewsCardData::from(&article);
+        assert!(!card.url_valid);
+        assert_eq!(card.url, "htp:/broken link");
+        let json = r#"{"title": "Fine", "url": "https://example.com/fine"}"#;
+        let article: Article = serde_json::from_str(json).unwrap();
+        assert!(NewsCardData::from(&article).url_valid);
+    }
+
+    #[test]
+    fn meslo_without_a_proportional_family_warns() {
+        let mut fonts = FontDefinitions::default();
+        fonts.families.remove(&FontFamily::Proportional);
+        let mut meslo = None;
+        let logged = logs(|| meslo = Some(with_meslo(fonts)));
+        let meslo = meslo.unwrap();
+        assert!(logged.contains("WARN"), "{}", logged);
+        assert!(logged.contains("no proportional font family"), "{}", logged);
+        assert!(!meslo.families.contains_key(&FontFamily::Proportional));
+    }
+
+    #[test]
+    fn meslo_comes_first_among_proportional_fonts() {
+        let fonts = with_meslo(FontDefinitions::default());
+        assert_eq!(fonts.families[&FontFamily::Proportional][0], "MesloLGS");
     }
 }
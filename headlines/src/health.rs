@@ -0,0 +1,90 @@
+use std::collections::VecDeque;
+
+use eframe::egui::{Color32, Response, Sense, Ui, Vec2};
+
+use crate::{
+    headlines::{Headlines, AMBER, RED},
+    i18n::{t, tf},
+    SourceKind,
+};
+
+const GREEN: Color32 = Color32::from_rgb(0, 190, 90);
+/// Fetches the error rate is taken over.
+const RECENT_FETCHES: usize = 10;
+/// Seconds without a successful fetch before a source counts as stale.
+const STALE_AFTER: f64 = 60.0 * 60.0;
+
+/// How fetches from one source have gone this session.
+#[derive(Default)]
+pub struct SourceHealth {
+    /// `ctx.input().time` of the last successful fetch.
+    last_success: Option<f64>,
+    /// Whether each of the latest fetches succeeded, oldest first.
+    recent: VecDeque<bool>,
+}
+
+impl SourceHealth {
+    fn record(&mut self, ok: bool, time: f64) {
+        if ok {
+            self.last_success = Some(time);
+        }
+        if self.recent.len() == RECENT_FETCHES {
+            self.recent.pop_front();
+        }
+        self.recent.push_back(ok);
+    }
+
+    fn error_rate(&self) -> f32 {
+        let failed = self.recent.iter().filter(|ok| !**ok).count();
+        failed as f32 / self.recent.len().max(1) as f32
+    }
+
+    /// Red when most recent fetches failed, amber when some did or nothing has come
+    /// through in a while, green otherwise. `None` until the source has been fetched from.
+    fn color(&self, now: f64) -> Option<Color32> {
+        if self.recent.is_empty() {
+            return None;
+        }
+        let rate = self.error_rate();
+        let stale = self.last_success.map_or(true, |at| now - at > STALE_AFTER);
+        Some(if rate >= 0.5 {
+            RED
+        } else if rate > 0.0 || stale {
+            AMBER
+        } else {
+            GREEN
+        })
+    }
+}
+
+impl Headlines {
+    pub(crate) fn record_fetch(&mut self, ok: bool, time: f64) {
+        let source = self.config.source;
+        match self.source_health.iter_mut().find(|(kind, _)| *kind == source) {
+            Some((_, health)) => health.record(ok, time),
+            None => {
+                let mut health = SourceHealth::default();
+                health.record(ok, time);
+                self.source_health.push((source, health));
+            }
+        }
+    }
+
+    /// A small dot coloured by how fetches from `source` have gone, with the details on
+    /// hover. Takes no space for sources that haven't been fetched from yet.
+    pub(crate) fn health_dot(&self, ui: &mut Ui, source: SourceKind) -> Option<Response> {
+        let now = ui.input().time;
+        let health = self.source_health.iter().find(|(kind, _)| *kind == source)?;
+        let health = &health.1;
+        let color = health.color(now)?;
+        let (rect, response) = ui.allocate_exact_size(Vec2::splat(10.0), Sense::hover());
+        ui.painter().circle_filled(rect.center(), 4.0, color);
+        let last_success = match health.last_success {
+            Some(at) => tf("health_last_success", &[&((now - at) / 60.0).round()]),
+            None => t("health_never_succeeded").to_string(),
+        };
+        let rate = (health.error_rate() * 100.0).round();
+        let errors = tf("health_error_rate", &[&rate, &health.recent.len()]);
+        Some(response.on_hover_text(format!("{}\n{}", last_success, errors)))
+    }
+}
@@ -0,0 +1,84 @@
+use std::{
+    collections::HashMap,
+    fmt::Display,
+    sync::{
+        atomic::{AtomicU8, Ordering},
+        OnceLock,
+    },
+};
+
+use serde::{Deserialize, Serialize};
+
+type Table = HashMap<&'static str, &'static str>;
+
+static LANGUAGE: AtomicU8 = AtomicU8::new(Language::En as u8);
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Language {
+    #[default]
+    En,
+    Es,
+}
+
+impl Language {
+    pub const ALL: [Language; 2] = [Language::En, Language::Es];
+
+    /// The language's name in itself, for the language picker.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Language::En => "English",
+            Language::Es => "Español",
+        }
+    }
+
+    fn table(&self) -> &'static Table {
+        static EN: OnceLock<Table> = OnceLock::new();
+        static ES: OnceLock<Table> = OnceLock::new();
+        match self {
+            Language::En => EN.get_or_init(|| parse(include_str!("../i18n/en.txt"))),
+            Language::Es => ES.get_or_init(|| parse(include_str!("../i18n/es.txt"))),
+        }
+    }
+}
+
+/// Parses a string table: `key = value` lines, blank lines and `#` comments ignored.
+fn parse(src: &'static str) -> Table {
+    src.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| line.split_once('='))
+        .map(|(key, value)| (key.trim(), value.trim()))
+        .collect()
+}
+
+/// Switches the language `t` looks strings up in.
+pub fn set_language(language: Language) {
+    LANGUAGE.store(language as u8, Ordering::Relaxed);
+}
+
+fn current() -> Language {
+    match LANGUAGE.load(Ordering::Relaxed) {
+        l if l == Language::Es as u8 => Language::Es,
+        _ => Language::En,
+    }
+}
+
+/// Looks up the UI string for `key` in the current language, falling back
+/// to English and then to the key itself.
+pub fn t(key: &'static str) -> &'static str {
+    current()
+        .table()
+        .get(key)
+        .or_else(|| Language::En.table().get(key))
+        .copied()
+        .unwrap_or(key)
+}
+
+/// Like [`t`], filling each `{}` in the string with the next of `args`.
+pub fn tf(key: &'static str, args: &[&dyn Display]) -> String {
+    let mut out = t(key).to_string();
+    for arg in args {
+        out = out.replacen("{}", &arg.to_string(), 1);
+    }
+    out
+}
@@ -0,0 +1,89 @@
+use std::sync::atomic::{AtomicU8, Ordering};
+
+use serde::{Deserialize, Serialize};
+
+use crate::i18n::t;
+
+static ICON_SET: AtomicU8 = AtomicU8::new(IconSet::Emoji as u8);
+
+/// How buttons and markers draw their glyphs. Emoji can show up as tofu boxes with
+/// some system fonts, so plain ASCII stand-ins are offered as well.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum IconSet {
+    #[default]
+    Emoji,
+    Text,
+}
+
+impl IconSet {
+    pub const ALL: [IconSet; 2] = [IconSet::Emoji, IconSet::Text];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            IconSet::Emoji => t("icons_emoji"),
+            IconSet::Text => t("icons_text"),
+        }
+    }
+}
+
+/// Switches the icon set `icon` and `plain` draw with.
+pub fn set_icon_set(set: IconSet) {
+    ICON_SET.store(set as u8, Ordering::Relaxed);
+}
+
+fn current() -> IconSet {
+    match ICON_SET.load(Ordering::Relaxed) {
+        s if s == IconSet::Text as u8 => IconSet::Text,
+        _ => IconSet::Emoji,
+    }
+}
+
+/// The glyph to draw for `emoji` in the current icon set.
+pub fn icon(emoji: &'static str) -> &'static str {
+    if current() == IconSet::Emoji {
+        return emoji;
+    }
+    match emoji {
+        "❌" | "✖" => "x",
+        "🔄" => "R",
+        "🔔" => "N+",
+        "🔕" => "N-",
+        "📰" => "Feed",
+        "🔖" => "BM",
+        "📥" => "RL",
+        "◫" => "||",
+        "📶" => "Net",
+        "📵" => "Sav",
+        "🖼" => "[img]",
+        "☕" => "Dg",
+        "⚙" => "Set",
+        "☰" => "=",
+        "🌐" => "Tr",
+        "🌞" => "Lt",
+        "🌙" => "Dk",
+        "📋" => "Cp",
+        "⤢" => "<>",
+        "📓" => "#",
+        "📌" => "Pin",
+        "❝" => "\"",
+        "★" => "*",
+        "☆" => "+",
+        "▶" => ">",
+        "▼" => "v",
+        "…" => "...",
+        other => other,
+    }
+}
+
+/// `text` with its emoji and symbols dropped when the plain-text icon set is on,
+/// for UI strings like "read more ⤴" that carry a glyph of their own.
+pub fn plain(text: &str) -> String {
+    if current() == IconSet::Emoji {
+        return text.to_string();
+    }
+    text.chars()
+        .filter(|c| !matches!(c, '\u{2190}'..='\u{2BFF}' | '\u{1F000}'..='\u{1FFFF}' | '\u{FE0F}'))
+        .collect::<String>()
+        .trim()
+        .to_string()
+}
@@ -0,0 +1,212 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::mpsc::{channel, Receiver, Sender},
+};
+
+use eframe::egui::{ColorImage, Context, TextureHandle};
+
+#[cfg(not(target_arch = "wasm32"))]
+use crate::source::http_agent;
+
+/// Longest side, in pixels, thumbnails are downscaled to before upload.
+const THUMBNAIL_SIZE: u32 = 240;
+/// Default for [`ImageLoader::max_downloads`].
+pub const MAX_IMAGE_DOWNLOADS: usize = 4;
+
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct ImageKey {
+    pub url: String,
+    pub full: bool,
+}
+
+impl ImageKey {
+    pub fn thumbnail(url: &str) -> Self {
+        ImageKey {
+            url: url.to_string(),
+            full: false,
+        }
+    }
+
+    pub fn full(url: &str) -> Self {
+        ImageKey {
+            url: url.to_string(),
+            full: true,
+        }
+    }
+}
+
+pub enum ImageState {
+    Loading,
+    Loaded(TextureHandle),
+    Failed(String),
+}
+
+type ImageResult = (ImageKey, Result<ColorImage, String>);
+
+/// Downloads and decodes article images off the UI thread and keeps the
+/// resulting textures around for as long as the loader lives.
+pub struct ImageLoader {
+    states: HashMap<ImageKey, ImageState>,
+    tx: Sender<ImageResult>,
+    rx: Receiver<ImageResult>,
+    /// Images waiting for a download slot, the front one starts next.
+    queue: VecDeque<ImageKey>,
+    in_flight: usize,
+    /// How many downloads may run at once, so a long feed can't saturate the connection.
+    pub max_downloads: usize,
+    /// Wakes the UI when a download finishes, picked up from the first `poll`.
+    repaint: Option<Context>,
+    #[cfg(not(target_arch = "wasm32"))]
+    agent: ureq::Agent,
+}
+
+impl Default for ImageLoader {
+    fn default() -> Self {
+        let (tx, rx) = channel();
+        ImageLoader {
+            states: HashMap::new(),
+            tx,
+            rx,
+            queue: VecDeque::new(),
+            in_flight: 0,
+            max_downloads: MAX_IMAGE_DOWNLOADS,
+            repaint: None,
+            #[cfg(not(target_arch = "wasm32"))]
+            agent: http_agent(false),
+        }
+    }
+}
+
+impl ImageLoader {
+    /// What downloads from now on go out with.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn set_agent(&mut self, agent: ureq::Agent) {
+        self.agent = agent;
+    }
+
+    /// Returns the image's state, queueing the download the first time it's asked for.
+    /// `visible` images jump the queue, so what's on screen loads first.
+    pub fn get(&mut self, key: &ImageKey, visible: bool) -> &ImageState {
+        if !self.states.contains_key(key) {
+            self.states.insert(key.clone(), ImageState::Loading);
+            if visible {
+                self.queue.push_front(key.clone());
+            } else {
+                self.queue.push_back(key.clone());
+            }
+        } else if visible {
+            if let Some(i) = self.queue.iter().position(|k| k == key).filter(|&i| i > 0) {
+                if let Some(key) = self.queue.remove(i) {
+                    self.queue.push_front(key);
+                }
+            }
+        }
+        self.start_queued();
+        &self.states[key]
+    }
+
+    fn start_queued(&mut self) {
+        while self.in_flight < self.max_downloads.max(1) {
+            let key = match self.queue.pop_front() {
+                Some(key) => key,
+                None => return,
+            };
+            self.in_flight += 1;
+            #[cfg(not(target_arch = "wasm32"))]
+            spawn_load(self.agent.clone(), key, self.tx.clone(), self.repaint.clone());
+            #[cfg(target_arch = "wasm32")]
+            spawn_load(key, self.tx.clone(), self.repaint.clone());
+        }
+    }
+
+    /// Turns every image decoded since the last call into a texture.
+    pub fn poll(&mut self, ctx: &Context) {
+        if self.repaint.is_none() {
+            self.repaint = Some(ctx.clone());
+        }
+        while let Ok((key, result)) = self.rx.try_recv() {
+            self.in_flight = self.in_flight.saturating_sub(1);
+            let state = match result {
+                Ok(image) => ImageState::Loaded(ctx.load_texture(&key.url, image)),
+                Err(e) => {
+                    tracing::warn!("failed loading image {}: {}", key.url, e);
+                    ImageState::Failed(e)
+                }
+            };
+            self.states.insert(key, state);
+        }
+        self.start_queued();
+    }
+
+    pub fn clear(&mut self) {
+        self.states.clear();
+        self.queue.clear();
+    }
+}
+
+fn decode(bytes: &[u8], full: bool) -> Result<ColorImage, String> {
+    let image = image::load_from_memory(bytes).map_err(|e| e.to_string())?;
+    let image = if full {
+        image
+    } else {
+        image.thumbnail(THUMBNAIL_SIZE, THUMBNAIL_SIZE)
+    };
+    let size = [image.width() as usize, image.height() as usize];
+    let rgba = image.to_rgba8();
+    Ok(ColorImage::from_rgba_unmultiplied(size, rgba.as_raw()))
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn spawn_load(
+    agent: ureq::Agent,
+    key: ImageKey,
+    tx: Sender<ImageResult>,
+    repaint: Option<Context>,
+) {
+    std::thread::spawn(move || {
+        let result = download(&agent, &key.url).and_then(|bytes| decode(&bytes, key.full));
+        if let Err(e) = tx.send((key, result)) {
+            tracing::error!("Error sending image: {}", e);
+        }
+        if let Some(ctx) = repaint {
+            ctx.request_repaint();
+        }
+    });
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn download(agent: &ureq::Agent, url: &str) -> Result<Vec<u8>, String> {
+    use std::io::Read;
+
+    let response = agent.get(url).call().map_err(|e| e.to_string())?;
+    let mut bytes = Vec::new();
+    response
+        .into_reader()
+        .read_to_end(&mut bytes)
+        .map_err(|e| e.to_string())?;
+    Ok(bytes)
+}
+
+#[cfg(target_arch = "wasm32")]
+fn spawn_load(key: ImageKey, tx: Sender<ImageResult>, repaint: Option<Context>) {
+    wasm_bindgen_futures::spawn_local(async move {
+        let result = download(&key.url)
+            .await
+            .and_then(|bytes| decode(&bytes, key.full));
+        if let Err(e) = tx.send((key, result)) {
+            tracing::error!("Error sending image: {}", e);
+        }
+        if let Some(ctx) = repaint {
+            ctx.request_repaint();
+        }
+    });
+}
+
+#[cfg(target_arch = "wasm32")]
+async fn download(url: &str) -> Result<Vec<u8>, String> {
+    let response = reqwasm::http::Request::get(url)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+    response.binary().await.map_err(|e| e.to_string())
+}
@@ -1,56 +1,228 @@
+mod blend;
+mod bookmark_io;
+mod bookmarks;
+mod changelog;
+mod citation;
+#[cfg(not(target_arch = "wasm32"))]
+mod config_file;
+mod connection;
+mod crash;
+mod deferred;
+mod digest;
 mod headlines;
+mod health;
+mod i18n;
+mod icons;
+mod images;
+mod line_style;
+mod locale;
+#[cfg(not(target_arch = "wasm32"))]
+mod notify;
+mod numbers;
+mod paging;
+mod post_process;
+mod pull;
+mod read_later;
+#[cfg(not(target_arch = "wasm32"))]
+mod reader;
+mod searches;
+mod source;
+mod source_filter;
+mod split;
+mod stats;
+mod text;
+mod translate;
+mod wake;
+
+use std::panic::{self, AssertUnwindSafe};
 
 use eframe::{
     egui::{
-        CentralPanel, Context, Hyperlink, Label, RichText, ScrollArea, Separator, TextStyle,
-        TopBottomPanel, Ui, Visuals,
+        CentralPanel, Color32, Context, Hyperlink, Label, RichText, ScrollArea, Separator,
+        TextStyle, TopBottomPanel, Ui, Visuals,
     },
     App,
 };
-pub use headlines::{Headlines, Msg, NewsCardData, PADDING};
+
+use crate::headlines::AMBER;
+use crate::i18n::{t, tf};
+pub use bookmarks::Bookmark;
+pub use citation::CitationStyle;
+pub use headlines::{
+    FetchEvent, FetchState, Headlines, Msg, NewsCardData, View, PADDING, STORAGE_KEY,
+};
+#[cfg(not(target_arch = "wasm32"))]
+pub use headlines::PROFILE_ENV;
+pub use i18n::Language;
+pub use icons::IconSet;
+pub use line_style::{Emphasis, LineStyle};
+pub use numbers::{format_count, NumberFormat};
+pub use post_process::{NoPostProcess, PostProcess};
+pub use searches::SavedSearch;
+pub use source::{
+    ArticleSource, BoxedSource, DemoSource, Fetched, HomeFeed, Query, RssSource, SearchScope,
+    SourceKind,
+};
+pub use translate::{NoopTranslator, Translator};
+#[cfg(not(target_arch = "wasm32"))]
+pub use config_file::{WindowOptions, APP_NAME};
+#[cfg(not(target_arch = "wasm32"))]
+pub use reader::Extractor;
 
 impl App for Headlines {
+    /// Draws the frame, or the crash screen once drawing one panicked so a broken frame
+    /// doesn't leave a dead window. Wasm builds abort instead, see `install_panic_page`.
     fn update(&mut self, ctx: &eframe::egui::Context, frame: &mut eframe::Frame) {
-        ctx.request_repaint();
-        // ctx.set_debug_on_hover(true);
+        if self.crash.is_some() {
+            self.render_crash(ctx);
+            return;
+        }
+        let drawn = panic::catch_unwind(AssertUnwindSafe(|| self.render_frame(ctx, frame)));
+        if let Err(payload) = drawn {
+            self.crash = Some(crash::panic_message(payload.as_ref()));
+            ctx.request_repaint();
+        }
+    }
 
-        if self.config.dark_mode {
-            ctx.set_visuals(Visuals::dark());
-        } else {
-            ctx.set_visuals(Visuals::light());
+    fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        let api_key = self.stored_api_key().to_string();
+        let api_key = std::mem::replace(&mut self.config.api_key, api_key);
+        eframe::set_value(storage, &self.storage_key, &self.config);
+        self.config.api_key = api_key;
+    }
+}
+
+impl Headlines {
+    fn render_frame(&mut self, ctx: &eframe::egui::Context, frame: &mut eframe::Frame) {
+        if !self.config.pause_when_idle {
+            ctx.request_repaint();
         }
+        ctx.set_debug_on_hover(self.config.debug_overlay);
+        i18n::set_language(self.config.language);
+        icons::set_icon_set(self.config.icon_set);
+        numbers::set_number_format(self.config.number_format);
+        #[cfg(target_arch = "wasm32")]
+        self.start_fetching();
+
+        let mut visuals = if self.config.dark_mode {
+            Visuals::dark()
+        } else {
+            Visuals::light()
+        };
+        visuals.selection.bg_fill = self.config.accent_color;
+        ctx.set_visuals(visuals);
 
         if !self.api_key_initialized {
             self.render_config(ctx);
         } else {
-            self.preload_articles();
+            // one event is handled per frame, so keep going until the channel is drained
+            let received = self.preload_articles(ctx);
+            self.images.max_downloads = self.config.max_image_downloads;
+            self.images.poll(ctx);
+            if received || self.is_animating() {
+                ctx.request_repaint();
+            }
 
-            self.render_top_panel(ctx, frame);
-            render_footer(ctx);
+            #[cfg(not(target_arch = "wasm32"))]
+            {
+                self.apply_pending_window_size(frame);
+                self.update_window_title(frame);
+                if self.window.ticker {
+                    self.render_ticker(ctx);
+                    return;
+                }
+            }
 
+            self.handle_category_keys(ctx);
+            self.handle_reading_mode_key(ctx);
+            self.handle_search_focus_key(ctx);
+            self.handle_card_keys(ctx);
+            self.auto_refresh(ctx.input().time);
+
+            #[cfg(not(target_arch = "wasm32"))]
+            self.render_tls_warning(ctx);
+            if !self.reading_mode {
+                if self.render_top_panel(ctx) {
+                    frame.quit();
+                }
+                render_footer(ctx);
+            }
+            self.render_settings(ctx);
+            self.render_stats(ctx);
+            self.render_about(ctx);
+            self.render_changelog(ctx);
+            self.render_toast(ctx);
+            self.render_lightbox(ctx);
+            #[cfg(not(target_arch = "wasm32"))]
+            self.render_reader(ctx);
+
+            if self.view == View::Feed && !self.reading_mode {
+                self.render_split_pane(ctx);
+            }
             CentralPanel::default().show(ctx, |ui| {
-                if self.articles.is_empty() {
-                    ui.vertical_centered_justified(|ui| {
-                        ui.heading("Loading ⌛");
+                if self.view == View::Bookmarks {
+                    ui.vertical_centered(|ui| {
+                        ui.heading(t("bookmarks"));
+                    });
+                    ScrollArea::vertical().show(ui, |ui| {
+                        self.render_bookmarks(ui);
+                    });
+                } else if self.view == View::ReadLater {
+                    ui.vertical_centered(|ui| {
+                        ui.heading(t("read_later_queue"));
                     });
-                } else {
-                    render_header(ui);
                     ScrollArea::vertical().show(ui, |ui| {
-                        self.render_news_cards(ui);
+                        self.render_read_later(ui);
                     });
+                } else if self.articles.is_empty() {
+                    ui.vertical_centered_justified(|ui| match &self.fetch_state {
+                        FetchState::Loading => {
+                            ui.heading(icons::plain(t("loading")));
+                        }
+                        FetchState::Done => {
+                            ui.heading(t("no_results"));
+                        }
+                        FetchState::Failed(e) => {
+                            ui.heading(t("feed_failed"));
+                            ui.label(e);
+                            if ui.button(t("retry")).clicked() {
+                                self.refresh(ui.input().time, true);
+                            }
+                        }
+                    });
+                } else {
+                    if !self.reading_mode {
+                        let accent = self.config.accent_color;
+                        render_header(ui, accent, self.articles.len(), self.total_results);
+                    }
+                    if !self.failed_categories.is_empty() {
+                        let names: Vec<&str> =
+                            self.failed_categories.iter().map(|c| c.name()).collect();
+                        let warning = tf("categories_failed", &[&names.join(", ")]);
+                        ui.colored_label(AMBER, warning);
+                    }
+                    let output = ScrollArea::vertical().show(ui, |ui| {
+                        if self.view == View::Digest {
+                            self.render_digest(ui);
+                        } else {
+                            self.render_news_cards(ui);
+                            self.render_load_more(ui);
+                        }
+                    });
+                    self.track_pull(ui, output.state.offset.y, output.inner_rect);
                 }
             });
         }
     }
-
-    fn save(&mut self, storage: &mut dyn eframe::Storage) {
-        eframe::set_value(storage, "headlines", &self.config);
-    }
 }
 
-fn render_header(ui: &mut Ui) {
+fn render_header(ui: &mut Ui, accent: Color32, shown: usize, total: Option<u64>) {
     ui.vertical_centered(|ui| {
-        ui.heading("Headlines");
+        ui.heading(RichText::new(t("headlines")).color(accent));
+        if let Some(total) = total {
+            let (shown, total) = (format_count(shown as u64), format_count(total));
+            ui.label(tf("showing_results", &[&shown, &total]));
+        }
     });
     ui.add_space(PADDING);
     let sep = Separator::default().spacing(20.0);
@@ -62,10 +234,10 @@ fn render_footer(ctx: &Context) {
         ui.vertical_centered(|ui| {
             ui.add_space(10.0);
             ui.add(Label::new(
-                RichText::new("API source: newsapi.org").monospace(),
+                RichText::new(t("api_source")).monospace(),
             ));
             ui.add(Hyperlink::from_label_and_url(
-                RichText::new("Made with egui").text_style(TextStyle::Monospace),
+                RichText::new(t("made_with_egui")).text_style(TextStyle::Monospace),
                 "https://github.com/emilk/egui",
             ));
             ui.add_space(10.0);
@@ -81,6 +253,7 @@ use eframe::wasm_bindgen::{self, prelude::*};
 pub fn main_web(canvas_id: &str) {
     let headlines = Headlines::new();
     tracing_wasm::set_as_global_default();
+    crash::install_panic_page();
     eframe::start_web(canvas_id, Box::new(|cc| Box::new(headlines.init(cc))))
         .expect("Failed to launch app");
 }
@@ -0,0 +1,95 @@
+use eframe::egui::{text::TextFormat, Button, Color32, ComboBox, RichText, Stroke, Ui};
+use serde::{Deserialize, Serialize};
+
+use crate::{headlines::Headlines, i18n::t};
+
+/// What sets a folded line apart besides its color. egui's default fonts have no bold,
+/// so this is as close to a weight as lines can get.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Emphasis {
+    #[default]
+    Plain,
+    Italic,
+    Underline,
+}
+
+impl Emphasis {
+    pub const ALL: [Emphasis; 3] = [Emphasis::Plain, Emphasis::Italic, Emphasis::Underline];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Emphasis::Plain => t("emphasis_plain"),
+            Emphasis::Italic => t("emphasis_italic"),
+            Emphasis::Underline => t("emphasis_underline"),
+        }
+    }
+}
+
+/// How the title of a folded card is drawn, one for read cards and one for unread.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LineStyle {
+    /// `None` follows the theme.
+    pub color: Option<Color32>,
+    pub emphasis: Emphasis,
+}
+
+impl LineStyle {
+    pub(crate) fn color_or(&self, theme: Color32) -> Color32 {
+        self.color.unwrap_or(theme)
+    }
+
+    pub(crate) fn rich(&self, text: RichText, color: Color32) -> RichText {
+        let text = text.color(color);
+        match self.emphasis {
+            Emphasis::Plain => text,
+            Emphasis::Italic => text.italics(),
+            Emphasis::Underline => text.underline(),
+        }
+    }
+
+    pub(crate) fn format(&self, format: TextFormat) -> TextFormat {
+        match self.emphasis {
+            Emphasis::Plain => format,
+            Emphasis::Italic => TextFormat {
+                italics: true,
+                ..format
+            },
+            Emphasis::Underline => TextFormat {
+                underline: Stroke::new(1.0, format.color),
+                ..format
+            },
+        }
+    }
+}
+
+impl Headlines {
+    /// Colors and emphasis for read and unread lines in compact and collapsed cards.
+    pub fn render_line_styles(&mut self, ui: &mut Ui) {
+        let text = if self.config.dark_mode { Color32::WHITE } else { Color32::BLACK };
+        let weak = ui.visuals().weak_text_color();
+        let lines = [
+            ("unread_lines", &mut self.config.unread_line, text),
+            ("read_lines", &mut self.config.read_line, weak),
+        ];
+        for (label, style, theme) in lines {
+            ui.horizontal(|ui| {
+                let mut color = style.color_or(theme);
+                if ui.color_edit_button_srgba(&mut color).changed() {
+                    style.color = Some(color);
+                }
+                ComboBox::from_id_source(label)
+                    .selected_text(style.emphasis.name())
+                    .show_ui(ui, |ui| {
+                        for emphasis in Emphasis::ALL {
+                            ui.selectable_value(&mut style.emphasis, emphasis, emphasis.name());
+                        }
+                    });
+                ui.label(t(label));
+                let is_default = *style == LineStyle::default();
+                if ui.add_enabled(!is_default, Button::new(t("reset_default"))).clicked() {
+                    *style = LineStyle::default();
+                }
+            });
+        }
+    }
+}
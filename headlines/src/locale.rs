@@ -0,0 +1,55 @@
+use newsapi::Country;
+
+/// The country newsapi should default to for this system's locale, if it covers it.
+pub fn detect_country() -> Option<Country> {
+    let locale = system_locale()?;
+    let country = country_from_locale(&locale);
+    tracing::debug!("locale {:?} maps to {:?}", locale, country);
+    country
+}
+
+/// Picks the region out of a locale like `en_GB.UTF-8` or `pt-BR`.
+/// Locales without one, such as `de` or `C`, give `None`.
+fn country_from_locale(locale: &str) -> Option<Country> {
+    let locale = locale.split(['.', '@']).next()?;
+    let region = locale.split(['_', '-']).nth(1)?;
+    Country::from_code(region)
+}
+
+/// The user's locale from the OS: `LC_ALL`, `LC_MESSAGES` or `LANG` on Linux and BSD, the
+/// user default locale on Windows and the preferred languages on macOS.
+#[cfg(not(target_arch = "wasm32"))]
+fn system_locale() -> Option<String> {
+    sys_locale::get_locale()
+}
+
+#[cfg(target_arch = "wasm32")]
+fn system_locale() -> Option<String> {
+    web_sys::window()?.navigator().language()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_region_is_read_from_both_separators() {
+        assert_eq!(country_from_locale("en_GB.UTF-8"), Some(Country::Gb));
+        assert_eq!(country_from_locale("pt-BR"), Some(Country::Br));
+        assert_eq!(country_from_locale("sr_RS@latin"), Some(Country::Rs));
+    }
+
+    #[test]
+    fn locales_without_a_region_have_no_country() {
+        assert_eq!(country_from_locale("de"), None);
+        assert_eq!(country_from_locale("C"), None);
+        assert_eq!(country_from_locale("C.UTF-8"), None);
+        assert_eq!(country_from_locale(""), None);
+    }
+
+    #[test]
+    fn regions_newsapi_doesnt_cover_have_no_country() {
+        assert_eq!(country_from_locale("es_419"), None);
+        assert_eq!(country_from_locale("ca_AD.UTF-8"), None);
+    }
+}
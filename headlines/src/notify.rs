@@ -0,0 +1,119 @@
+use std::time::{Duration, Instant};
+
+use notify_rust::Notification;
+
+use crate::{
+    i18n::{t, tf},
+    NewsCardData,
+};
+
+/// How long to wait after the last new article before posting, so a full
+/// refresh results in a single notification.
+const DEBOUNCE: Duration = Duration::from_secs(2);
+/// Seen article ids kept in the config, the oldest are forgotten first.
+const SEEN_HISTORY: usize = 2000;
+
+/// Posts an OS notification for headlines that aren't among the seen ids stored in the
+/// config, so nothing already shown before a restart or in another feed is posted again.
+#[derive(Default)]
+pub struct HeadlineNotifier {
+    /// Nothing was seen yet when the current fetch started, so it only fills `seen`.
+    baseline: bool,
+    pending: Vec<String>,
+    last_new: Option<Instant>,
+}
+
+impl HeadlineNotifier {
+    /// Called as each fetch starts, with the stored seen ids.
+    pub fn fetch_started(&mut self, seen: &[String]) {
+        self.baseline = seen.is_empty();
+    }
+
+    /// Queues `article` for the next notification unless it's in `seen`, then adds it
+    /// there, forgetting the oldest ids past `SEEN_HISTORY`.
+    pub fn observe(&mut self, article: &NewsCardData, seen: &mut Vec<String>) {
+        if seen.iter().any(|id| id == article.id()) {
+            return;
+        }
+        seen.push(article.id().to_string());
+        let excess = seen.len().saturating_sub(SEEN_HISTORY);
+        seen.drain(..excess);
+        if !self.baseline {
+            self.pending.push(article.title.clone());
+            self.last_new = Some(Instant::now());
+        }
+    }
+
+    /// Drops what's waiting to be posted, e.g. when switching to a different feed.
+    pub fn reset(&mut self) {
+        self.pending.clear();
+        self.last_new = None;
+    }
+
+    /// Whether new headlines are waiting out the debounce. Frames have to keep coming
+    /// until they're posted, paused or not, since `flush` only runs in a frame.
+    pub fn is_pending(&self) -> bool {
+        !self.pending.is_empty()
+    }
+
+    pub fn flush(&mut self) {
+        let due = self.last_new.is_some_and(|t| t.elapsed() >= DEBOUNCE);
+        if !due || self.pending.is_empty() {
+            return;
+        }
+        let body = match self.pending.len() {
+            1 => self.pending[0].clone(),
+            n => tf("notification_more", &[&self.pending[0], &(n - 1)]),
+        };
+        if let Err(e) = Notification::new().summary(t("headlines")).body(&body).show() {
+            tracing::error!("failed showing notification: {}", e);
+        }
+        self.pending.clear();
+        self.last_new = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn card(id: &str) -> NewsCardData {
+        NewsCardData::new(id, &format!("https://example.com/{}", id))
+    }
+
+    #[test]
+    fn the_first_feed_ever_is_only_remembered() {
+        let mut notifier = HeadlineNotifier::default();
+        let mut seen = Vec::new();
+        notifier.fetch_started(&seen);
+        notifier.observe(&card("a"), &mut seen);
+        notifier.observe(&card("b"), &mut seen);
+        assert!(!notifier.is_pending());
+        assert_eq!(seen.len(), 2);
+    }
+
+    #[test]
+    fn only_unseen_articles_are_posted() {
+        let mut notifier = HeadlineNotifier::default();
+        // as loaded from the config after a restart
+        let mut seen = vec![card("a").id().to_string()];
+        notifier.fetch_started(&seen);
+        notifier.observe(&card("a"), &mut seen);
+        assert!(!notifier.is_pending());
+        notifier.observe(&card("b"), &mut seen);
+        assert_eq!(notifier.pending, ["b"]);
+        assert_eq!(seen.len(), 2);
+    }
+
+    #[test]
+    fn seen_ids_are_bounded() {
+        let mut notifier = HeadlineNotifier::default();
+        let mut seen = Vec::new();
+        notifier.fetch_started(&seen);
+        for i in 0..SEEN_HISTORY + 5 {
+            notifier.observe(&card(&i.to_string()), &mut seen);
+        }
+        assert_eq!(seen.len(), SEEN_HISTORY);
+        assert_eq!(seen[0], card("5").id());
+    }
+}
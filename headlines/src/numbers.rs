@@ -0,0 +1,114 @@
+use std::sync::atomic::{AtomicU8, Ordering};
+
+use serde::{Deserialize, Serialize};
+
+use crate::i18n::{t, tf};
+
+static NUMBER_FORMAT: AtomicU8 = AtomicU8::new(NumberFormat::Grouped as u8);
+
+/// How counts such as result totals and unread badges are written.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum NumberFormat {
+    /// "1,342"
+    #[default]
+    Grouped,
+    /// "1.3k"
+    Compact,
+}
+
+impl NumberFormat {
+    pub const ALL: [NumberFormat; 2] = [NumberFormat::Grouped, NumberFormat::Compact];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            NumberFormat::Grouped => t("numbers_grouped"),
+            NumberFormat::Compact => t("numbers_compact"),
+        }
+    }
+}
+
+/// Switches the style `format_count` writes in.
+pub fn set_number_format(format: NumberFormat) {
+    NUMBER_FORMAT.store(format as u8, Ordering::Relaxed);
+}
+
+fn current() -> NumberFormat {
+    match NUMBER_FORMAT.load(Ordering::Relaxed) {
+        f if f == NumberFormat::Compact as u8 => NumberFormat::Compact,
+        _ => NumberFormat::Grouped,
+    }
+}
+
+/// Formats a count in the current [`NumberFormat`] with the language's separators, e.g.
+/// `1342` -> `1,342` or `1.3k`.
+pub fn format_count(n: u64) -> String {
+    match current() {
+        NumberFormat::Grouped => grouped(n),
+        NumberFormat::Compact => compact(n),
+    }
+}
+
+fn grouped(n: u64) -> String {
+    let separator = t("thousands_separator");
+    let digits = n.to_string();
+    let mut out = String::with_capacity(digits.len() + digits.len() / 3);
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i) % 3 == 0 {
+            out.push_str(separator);
+        }
+        out.push(c);
+    }
+    out
+}
+
+/// One decimal at most, dropped when it's 0, and plain digits below a thousand.
+fn compact(n: u64) -> String {
+    let (key, unit) = match n {
+        0..=999 => return n.to_string(),
+        1_000..=999_999 => ("count_thousands", 1_000),
+        1_000_000..=999_999_999 => ("count_millions", 1_000_000),
+        _ => ("count_billions", 1_000_000_000),
+    };
+    let tenths = n / (unit / 10);
+    let short = if tenths % 10 == 0 || tenths >= 1000 {
+        (tenths / 10).to_string()
+    } else {
+        format!("{}{}{}", tenths / 10, t("decimal_separator"), tenths % 10)
+    };
+    tf(key, &[&short])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn grouped_counts_get_thousands_separators() {
+        assert_eq!(grouped(0), "0");
+        assert_eq!(grouped(999), "999");
+        assert_eq!(grouped(1_000), "1,000");
+        assert_eq!(grouped(1_342), "1,342");
+        assert_eq!(grouped(123_456_789), "123,456,789");
+        assert_eq!(grouped(u64::MAX), "18,446,744,073,709,551,615");
+    }
+
+    #[test]
+    fn compact_counts_keep_one_decimal() {
+        assert_eq!(compact(999), "999");
+        assert_eq!(compact(1_000), "1k");
+        assert_eq!(compact(1_342), "1.3k");
+        assert_eq!(compact(1_399), "1.3k");
+        assert_eq!(compact(52_000), "52k");
+        assert_eq!(compact(999_999), "999k");
+        assert_eq!(compact(2_500_000), "2.5M");
+        assert_eq!(compact(7_000_000_000), "7B");
+    }
+
+    #[test]
+    fn format_count_follows_the_chosen_format() {
+        set_number_format(NumberFormat::Compact);
+        assert_eq!(format_count(1_342), "1.3k");
+        set_number_format(NumberFormat::Grouped);
+        assert_eq!(format_count(1_342), "1,342");
+    }
+}
@@ -0,0 +1,105 @@
+use eframe::egui::{Spinner, Ui};
+
+use crate::{headlines::Headlines, i18n::t, FetchState, Msg, NewsCardData, PADDING};
+
+/// How far down the feed, as a fraction of its height, the next page starts loading.
+const PREFETCH_AT: f32 = 0.7;
+
+/// Pages fetched past the first. The next one is fetched in the background once
+/// the reader is most of the way down, and held here until they ask for it.
+#[derive(Default)]
+pub struct Paging {
+    /// Pages appended to the feed after the first.
+    appended: u32,
+    /// Whether the next page has been asked for and hasn't arrived yet.
+    requested: bool,
+    next: Option<Vec<NewsCardData>>,
+    /// Whether "Load more" was clicked before the next page arrived.
+    waiting: bool,
+    /// Set once a page comes back empty or fails, there's nothing left to load.
+    exhausted: bool,
+}
+
+impl Headlines {
+    /// The "Load more" button under the feed. Has to be drawn inside the feed's
+    /// scroll area, at the bottom, as it measures how far down the reader is.
+    pub fn render_load_more(&mut self, ui: &mut Ui) {
+        if !self.has_more_pages() {
+            return;
+        }
+        let content = ui.min_rect();
+        let seen = (ui.clip_rect().bottom() - content.top()) / content.height().max(1.0);
+        // a feed still streaming in is short, so it would look read almost right away
+        if seen >= PREFETCH_AT && self.fetch_state == FetchState::Done {
+            self.prefetch_next_page();
+        }
+        ui.add_space(PADDING);
+        ui.vertical_centered(|ui| {
+            if self.paging.waiting {
+                ui.add(Spinner::new());
+            } else if ui.button(t("load_more")).clicked() {
+                self.load_more();
+            }
+        });
+        ui.add_space(PADDING);
+    }
+
+    fn has_more_pages(&self) -> bool {
+        let total = self.total_results.unwrap_or(0);
+        !self.paging.exhausted && !self.articles.is_empty() && (self.articles.len() as u64) < total
+    }
+
+    fn prefetch_next_page(&mut self) {
+        if self.paging.requested || self.paging.next.is_some() {
+            return;
+        }
+        self.paging.requested = true;
+        let page = self.paging.appended + 2;
+        tracing::debug!("prefetching page {}", page);
+        self.send(Msg::FetchPage(page));
+    }
+
+    /// Appends the prefetched page, or shows a spinner until it arrives.
+    fn load_more(&mut self) {
+        match self.paging.next.take() {
+            Some(articles) => self.append_page(articles),
+            None => {
+                self.paging.waiting = true;
+                self.prefetch_next_page();
+            }
+        }
+    }
+
+    fn append_page(&mut self, mut articles: Vec<NewsCardData>) {
+        self.paging.appended += 1;
+        if articles.is_empty() {
+            self.paging.exhausted = true;
+        }
+        articles.retain(|article| !self.articles.iter().any(|a| a.id() == article.id()));
+        self.add_articles(articles);
+        self.deferred.flush();
+        if self.config.max_run_per_source > 0 {
+            self.diversify_sources();
+        }
+    }
+
+    pub(crate) fn page_fetched(&mut self, articles: Vec<NewsCardData>) {
+        self.paging.requested = false;
+        if self.paging.waiting {
+            self.paging.waiting = false;
+            self.append_page(articles);
+        } else {
+            self.paging.next = Some(articles);
+        }
+    }
+
+    /// Gives up on paging once a page fails, newsapi rejects pages past its result cap.
+    pub(crate) fn page_failed(&mut self) {
+        if self.paging.requested {
+            self.paging = Paging {
+                exhausted: true,
+                ..Paging::default()
+            };
+        }
+    }
+}
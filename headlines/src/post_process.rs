@@ -0,0 +1,18 @@
+use crate::NewsCardData;
+
+/// Hook for filtering or enriching the feed before it's shown.
+///
+/// Gets only the articles that just arrived, one streamed article, a feed the source
+/// said hadn't changed or a further page, before they're drawn. The split pane's
+/// articles go through it the same way. Each article is passed once, so the feed already
+/// on screen is never processed again.
+pub trait PostProcess {
+    fn post_process(&self, articles: &mut Vec<NewsCardData>);
+}
+
+/// Default post-processor, leaves the feed untouched.
+pub struct NoPostProcess;
+
+impl PostProcess for NoPostProcess {
+    fn post_process(&self, _articles: &mut Vec<NewsCardData>) {}
+}
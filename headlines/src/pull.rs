@@ -0,0 +1,58 @@
+use std::f32::consts::TAU;
+
+use eframe::egui::{pos2, Rect, Shape, Stroke, Ui, Vec2};
+
+use crate::headlines::Headlines;
+
+/// Default points the feed has to be pulled down past its top to refresh.
+pub const PULL_REFRESH_DISTANCE: f32 = 120.0;
+/// Points a pull springs back by each frame it isn't pulled further.
+const PULL_DECAY: f32 = 8.0;
+const ARC_RADIUS: f32 = 12.0;
+const ARC_POINTS: usize = 32;
+
+impl Headlines {
+    /// Scrolling up or dragging down while the feed `rect` is already at the top (`offset`
+    /// 0) pulls it, an arc below its top edge fills up as it does and the feed refreshes
+    /// once the pull reaches `pull_refresh_distance`.
+    pub(crate) fn track_pull(&mut self, ui: &Ui, offset: f32, rect: Rect) {
+        let threshold = self.config.pull_refresh_distance;
+        let input = ui.input();
+        let hovered = input.pointer.hover_pos().is_some_and(|pos| rect.contains(pos));
+        let mut pulled = input.scroll_delta.y;
+        if input.pointer.primary_down() {
+            pulled += input.pointer.delta().y;
+        }
+        let now = input.time;
+        drop(input);
+
+        if threshold <= 0.0 || offset > 0.0 || !hovered || pulled < 0.0 {
+            self.pull = 0.0;
+            return;
+        }
+        self.pull = if pulled > 0.0 {
+            self.pull + pulled
+        } else {
+            (self.pull - PULL_DECAY).max(0.0)
+        };
+        if self.pull >= threshold {
+            self.pull = 0.0;
+            self.refresh(now, false);
+            return;
+        }
+        if self.pull > 0.0 {
+            let progress = self.pull / threshold;
+            let center = pos2(rect.center().x, rect.top() + ARC_RADIUS * 2.0);
+            let segments = ((ARC_POINTS as f32 * progress).ceil() as usize).max(1);
+            let points = (0..=segments)
+                .map(|i| {
+                    let angle = TAU * progress * i as f32 / segments as f32 - TAU / 4.0;
+                    center + ARC_RADIUS * Vec2::angled(angle)
+                })
+                .collect();
+            let stroke = Stroke::new(3.0, self.config.accent_color);
+            ui.painter().add(Shape::line(points, stroke));
+            ui.ctx().request_repaint();
+        }
+    }
+}
@@ -0,0 +1,73 @@
+use eframe::{
+    egui::{Button, Hyperlink, Layout, RichText, Separator, Ui},
+    emath::Align,
+};
+
+use crate::{
+    headlines::Headlines,
+    i18n::{t, tf},
+    icons::plain,
+    numbers::format_count,
+    text::is_web_url,
+    Bookmark, PADDING,
+};
+
+impl Headlines {
+    pub fn is_queued(&self, id: &str) -> bool {
+        self.config.read_later.iter().any(|q| q.id == id)
+    }
+
+    /// Adds the feed's article to the end of the read later queue, or takes it out again.
+    pub fn toggle_read_later(&mut self, id: &str) {
+        if self.is_queued(id) {
+            self.config.read_later.retain(|q| q.id != id);
+            return;
+        }
+        self.restore_deferred(id);
+        if let Some(a) = self.articles.iter().find(|a| a.id() == id) {
+            self.config.read_later.push(Bookmark::from(a));
+        }
+    }
+
+    /// The read later queue, oldest first. Unlike bookmarks, articles leave it once opened.
+    pub fn render_read_later(&mut self, ui: &mut Ui) {
+        if self.config.read_later.is_empty() {
+            ui.label(t("read_later_empty"));
+            return;
+        }
+        ui.label(tf("read_later_count", &[&format_count(self.config.read_later.len() as u64)]));
+        ui.add(Separator::default());
+
+        let mut done = None;
+        for q in &self.config.read_later {
+            ui.add_space(PADDING);
+            ui.label(RichText::new(&q.title).strong());
+            if !q.source.is_empty() {
+                ui.label(RichText::new(&q.source).small().weak());
+            }
+            if let Some(description) = &q.description {
+                ui.label(description);
+            }
+            ui.with_layout(Layout::right_to_left().with_cross_align(Align::Min), |ui| {
+                if is_web_url(&q.url) {
+                    let link = Hyperlink::from_label_and_url(plain(t("read_more")), &q.url);
+                    if ui.add(link).clicked() {
+                        done = Some((q.id.clone(), true));
+                    }
+                }
+                if ui.add(Button::new(t("remove")).small()).clicked() {
+                    done = Some((q.id.clone(), false));
+                }
+            });
+            ui.add_space(PADDING);
+            ui.add(Separator::default());
+        }
+        if let Some((id, opened)) = done {
+            self.config.read_later.retain(|q| q.id != id);
+            if opened {
+                self.config.stats.article_opened();
+                self.mark_read([id]);
+            }
+        }
+    }
+}
@@ -0,0 +1,115 @@
+use std::{
+    sync::{
+        mpsc::{channel, Receiver},
+        Arc,
+    },
+    thread,
+};
+
+use eframe::egui::{Context, Id, RichText, ScrollArea, Spinner, Window};
+
+use crate::{
+    headlines::Headlines,
+    i18n::{t, tf},
+    icons::plain,
+    NewsCardData,
+};
+
+/// Turns an article page's HTML into clean, readable text for the reader pane.
+///
+/// No extractor ships with the app; plug one in with
+/// [`Headlines::with_extractor`] to enable the "read here" action. Returning
+/// `None` means no article text could be found on the page.
+pub trait Extractor: Send + Sync {
+    fn extract(&self, html: &str) -> Option<String>;
+}
+
+pub enum ReaderState {
+    Loading,
+    Ready(String),
+    Failed(String),
+}
+
+/// An article opened in the in-app reader. Each pane owns its channel, so
+/// closing it or opening another article simply drops a pending result.
+pub struct ReaderPane {
+    title: String,
+    url: String,
+    state: ReaderState,
+    rx: Receiver<ReaderState>,
+}
+
+impl ReaderPane {
+    pub fn open(a: &NewsCardData, extractor: Arc<dyn Extractor>, agent: ureq::Agent) -> Self {
+        let (tx, rx) = channel();
+        let url = a.url.clone();
+        thread::spawn(move || {
+            let state = match agent.get(&url).call() {
+                Ok(response) => match response.into_string() {
+                    Ok(html) => match extractor.extract(&html) {
+                        Some(text) => ReaderState::Ready(text),
+                        None => ReaderState::Failed(t("no_article_text").to_string()),
+                    },
+                    Err(e) => ReaderState::Failed(e.to_string()),
+                },
+                Err(e) => ReaderState::Failed(e.to_string()),
+            };
+            // the pane may have been closed already, nothing to do then
+            let _ = tx.send(state);
+        });
+        ReaderPane {
+            title: a.title.clone(),
+            url: a.url.clone(),
+            state: ReaderState::Loading,
+            rx,
+        }
+    }
+}
+
+impl Headlines {
+    pub fn open_reader(&mut self, id: &str) {
+        let extractor = match &self.extractor {
+            Some(extractor) => extractor.clone(),
+            None => return,
+        };
+        if let Some(a) = self.articles.iter().find(|a| a.id() == id) {
+            self.reader = Some(ReaderPane::open(a, extractor, self.http.clone()));
+        }
+    }
+
+    pub fn render_reader(&mut self, ctx: &Context) {
+        let pane = match &mut self.reader {
+            Some(pane) => pane,
+            None => return,
+        };
+        if let Ok(state) = pane.rx.try_recv() {
+            pane.state = state;
+        }
+        let mut open = true;
+        Window::new(t("reader"))
+            .id(Id::new("reader"))
+            .open(&mut open)
+            .default_width(520.0)
+            .show(ctx, |ui| {
+                ui.heading(&pane.title);
+                ui.hyperlink_to(plain(t("open_original")), &pane.url);
+                ui.separator();
+                match &pane.state {
+                    ReaderState::Loading => {
+                        ui.add(Spinner::new());
+                    }
+                    ReaderState::Ready(text) => {
+                        ScrollArea::vertical().max_height(480.0).show(ui, |ui| {
+                            ui.label(text);
+                        });
+                    }
+                    ReaderState::Failed(e) => {
+                        ui.label(RichText::new(tf("article_failed", &[e])).weak());
+                    }
+                }
+            });
+        if !open {
+            self.reader = None;
+        }
+    }
+}
@@ -0,0 +1,82 @@
+use eframe::egui::{Button, TextEdit, Ui};
+use newsapi::Category;
+use serde::{Deserialize, Serialize};
+
+use crate::{headlines::Headlines, i18n::t, icons::icon, SearchScope};
+
+/// A named query the top panel offers as a one-click chip.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct SavedSearch {
+    pub name: String,
+    pub query: String,
+    pub scope: SearchScope,
+    pub category: Category,
+}
+
+impl Headlines {
+    /// Applies a saved search's query, scope and category in one go.
+    pub fn apply_saved_search(&mut self, saved: &SavedSearch) {
+        self.search = saved.query.clone();
+        self.config.search_scope = saved.scope;
+        self.config.category = saved.category;
+        self.submit_search();
+    }
+
+    pub fn render_saved_search_chips(&mut self, ui: &mut Ui) {
+        if self.config.saved_searches.is_empty() {
+            return;
+        }
+        let mut applied = None;
+        ui.horizontal_wrapped(|ui| {
+            for saved in &self.config.saved_searches {
+                let active = self.search.trim() == saved.query
+                    && self.config.search_scope == saved.scope
+                    && self.config.category == saved.category;
+                let chip = ui.selectable_label(active, &saved.name);
+                let (scope, category) = (saved.scope.name(), saved.category.name());
+                let tooltip = format!("{} · {} · {}", saved.query, scope, category);
+                if chip.on_hover_text(tooltip).clicked() {
+                    applied = Some(saved.clone());
+                }
+            }
+        });
+        if let Some(saved) = applied {
+            self.apply_saved_search(&saved);
+        }
+    }
+
+    pub fn render_saved_searches_settings(&mut self, ui: &mut Ui) {
+        ui.collapsing(t("saved_searches"), |ui| {
+            let mut removed = None;
+            for (i, saved) in self.config.saved_searches.iter().enumerate() {
+                ui.horizontal(|ui| {
+                    ui.label(&saved.name);
+                    ui.weak(format!("{} · {}", saved.query, saved.scope.name()));
+                    if ui.small_button(icon("✖")).on_hover_text(t("remove")).clicked() {
+                        removed = Some(i);
+                    }
+                });
+            }
+            if let Some(i) = removed {
+                self.config.saved_searches.remove(i);
+            }
+
+            ui.horizontal(|ui| {
+                let name = TextEdit::singleline(&mut self.new_search_name).hint_text(t("name"));
+                ui.add(name.desired_width(120.0));
+                let can_save =
+                    !self.new_search_name.trim().is_empty() && !self.search.trim().is_empty();
+                let save = Button::new(t("save_current_search"));
+                if ui.add_enabled(can_save, save).clicked() {
+                    self.config.saved_searches.push(SavedSearch {
+                        name: self.new_search_name.trim().to_string(),
+                        query: self.search.trim().to_string(),
+                        scope: self.config.search_scope,
+                        category: self.config.category,
+                    });
+                    self.new_search_name.clear();
+                }
+            });
+        });
+    }
+}
@@ -0,0 +1,486 @@
+#[cfg(not(target_arch = "wasm32"))]
+use std::sync::Arc;
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use async_trait::async_trait;
+use newsapi::{Category, Country, Endpoint, NewsAPI, NewsApiError};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    i18n::t,
+    text::{decode_entities, matches_filter},
+    NewsCardData,
+};
+
+/// What the feed should show, independent of the backend serving it.
+#[derive(Clone)]
+pub struct Query {
+    pub category: Category,
+    /// Keywords to search for, `None` for the plain feed.
+    pub search: Option<String>,
+    pub scope: SearchScope,
+    /// Keywords for an /everything home feed, `None` for top headlines.
+    pub home: Option<String>,
+    /// Comma separated newsapi source ids to limit the feed to.
+    pub sources: Option<String>,
+    /// Page of results, 1 for the first.
+    pub page: u32,
+    /// Articles per page, `None` for the source's default.
+    pub page_size: Option<u32>,
+    pub country: Country,
+    /// Categories fetched alongside `category` and merged into one feed.
+    pub blend: Vec<Category>,
+    /// Whether a newer fetch superseded this one, so what's left of it can be skipped.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub superseded: Option<Arc<dyn Fn() -> bool + Send + Sync>>,
+    #[cfg(target_arch = "wasm32")]
+    pub signal: Option<web_sys::AbortSignal>,
+}
+
+/// Which newsapi surface searches go to.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SearchScope {
+    #[default]
+    Headlines,
+    Everything,
+}
+
+impl SearchScope {
+    pub const ALL: [SearchScope; 2] = [SearchScope::Headlines, SearchScope::Everything];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            SearchScope::Headlines => t("scope_headlines"),
+            SearchScope::Everything => t("scope_everything"),
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            SearchScope::Headlines => t("scope_headlines_tip"),
+            SearchScope::Everything => t("scope_everything_tip"),
+        }
+    }
+}
+
+/// What the feed shows when nothing is being searched for.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum HomeFeed {
+    /// Curated top headlines for the selected category.
+    #[default]
+    TopHeadlines,
+    /// The /everything firehose, narrowed by `HeadlinesConfig::home_query`.
+    Everything,
+}
+
+impl HomeFeed {
+    pub const ALL: [HomeFeed; 2] = [HomeFeed::TopHeadlines, HomeFeed::Everything];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            HomeFeed::TopHeadlines => t("scope_headlines"),
+            HomeFeed::Everything => t("scope_everything"),
+        }
+    }
+}
+
+impl Query {
+    pub fn new(category: Category) -> Self {
+        Query {
+            category,
+            search: None,
+            scope: SearchScope::default(),
+            home: None,
+            sources: None,
+            page: 1,
+            page_size: None,
+            country: Country::default(),
+            blend: Vec::new(),
+            #[cfg(not(target_arch = "wasm32"))]
+            superseded: None,
+            #[cfg(target_arch = "wasm32")]
+            signal: None,
+        }
+    }
+}
+
+pub struct Fetched {
+    pub total_results: u64,
+    pub articles: Vec<NewsCardData>,
+    /// The source answered that nothing changed since it was last asked, `articles` are
+    /// the ones it sent then.
+    pub not_modified: bool,
+}
+
+/// Native fetches run on their own threads, so sources need to be shareable there.
+#[cfg(not(target_arch = "wasm32"))]
+pub type BoxedSource = Box<dyn ArticleSource + Send + Sync>;
+#[cfg(target_arch = "wasm32")]
+pub type BoxedSource = Box<dyn ArticleSource>;
+
+/// A backend the feed can be loaded from. Native fetches are polled on a worker thread
+/// of their own, so they may block it; wasm fetches run on the browser's event loop.
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+pub trait ArticleSource {
+    async fn fetch(&self, query: &Query) -> Result<Vec<NewsCardData>, NewsApiError>;
+
+    /// `fetch` along with how many results there are in all and whether anything
+    /// changed, for sources that can tell. Otherwise every result is on this page.
+    async fn fetch_page(&self, query: &Query) -> Result<Fetched, NewsApiError> {
+        let articles = self.fetch(query).await?;
+        Ok(Fetched {
+            total_results: articles.len() as u64,
+            articles,
+            not_modified: false,
+        })
+    }
+
+    /// Like `fetch_page`, but hands each article to `each` as soon as it's there, after
+    /// `started` got the total. What comes back has no articles, unless it's
+    /// `not_modified` and neither callback ran. Sources that get the whole page at once
+    /// can leave this be.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn fetch_each(
+        &self,
+        query: &Query,
+        started: &mut dyn FnMut(u64),
+        each: &mut dyn FnMut(NewsCardData),
+    ) -> Result<Fetched, NewsApiError>
+    where
+        Self: Sync,
+    {
+        let fetched = pollster::block_on(self.fetch_page(query))?;
+        if fetched.not_modified {
+            return Ok(fetched);
+        }
+        started(fetched.total_results);
+        fetched.articles.into_iter().for_each(each);
+        Ok(Fetched {
+            articles: Vec::new(),
+            ..fetched
+        })
+    }
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl ArticleSource for NewsAPI {
+    async fn fetch(&self, query: &Query) -> Result<Vec<NewsCardData>, NewsApiError> {
+        Ok(self.fetch_page(query).await?.articles)
+    }
+
+    async fn fetch_page(&self, query: &Query) -> Result<Fetched, NewsApiError> {
+        #[cfg(not(target_arch = "wasm32"))]
+        let response = with_query(self, query).fetch()?;
+        #[cfg(target_arch = "wasm32")]
+        let response = {
+            let mut api = with_query(self, query);
+            if let Some(signal) = &query.signal {
+                api.abort_signal(signal.clone());
+            }
+            api.fetch_web().await?
+        };
+        if response.not_modified() {
+            tracing::debug!("feed unchanged since the last fetch");
+        }
+        Ok(Fetched {
+            total_results: response.total_results(),
+            articles: response.articles().iter().map(NewsCardData::from).collect(),
+            not_modified: response.not_modified(),
+        })
+    }
+
+    /// Streams the articles as newsapi's response is decoded.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn fetch_each(
+        &self,
+        query: &Query,
+        started: &mut dyn FnMut(u64),
+        each: &mut dyn FnMut(NewsCardData),
+    ) -> Result<Fetched, NewsApiError> {
+        let api = with_query(self, query);
+        let response = api.fetch_each(started, |article| each(NewsCardData::from(&article)))?;
+        Ok(Fetched {
+            total_results: response.total_results(),
+            articles: response.articles().iter().map(NewsCardData::from).collect(),
+            not_modified: response.not_modified(),
+        })
+    }
+}
+
+fn with_query(api: &NewsAPI, query: &Query) -> NewsAPI {
+    let mut api = api.clone();
+    api.category(query.category);
+    api.country(query.country);
+    if let Some(search) = &query.search {
+        api.query(search);
+        if query.scope == SearchScope::Everything {
+            api.endpoint(Endpoint::Everything);
+        }
+    } else if let Some(home) = &query.home {
+        api.query(home);
+        api.endpoint(Endpoint::Everything);
+    }
+    if let Some(sources) = &query.sources {
+        api.sources(sources);
+    }
+    if query.page > 1 {
+        api.page(query.page);
+    }
+    if let Some(page_size) = query.page_size {
+        api.page_size(page_size);
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    if let Some(superseded) = query.superseded.clone() {
+        api.cancel_when(move || superseded());
+    }
+    api
+}
+
+/// A fixed offline feed, handy for trying the app out without an API key.
+pub struct DemoSource;
+
+impl DemoSource {
+    fn articles(&self, query: &Query) -> Vec<NewsCardData> {
+        // the whole demo feed fits on the first page
+        let count = if query.page > 1 { 0 } else { 5 };
+        (1..=count)
+            .map(|i| {
+                let title = format!("{} headline #{}", query.category.name(), i);
+                let url = format!("https://example.com/{}/{}", query.category, i);
+                let mut news = NewsCardData::new(&title, &url);
+                news.source = "Demo".to_string();
+                news.description = Some(format!("Sample {} story used by the demo source.", i));
+                news
+            })
+            .collect()
+    }
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl ArticleSource for DemoSource {
+    async fn fetch(&self, query: &Query) -> Result<Vec<NewsCardData>, NewsApiError> {
+        Ok(self.articles(query))
+    }
+}
+
+/// Items of an RSS 2.0 feed, for sites newsapi doesn't cover. Feeds have no categories
+/// or pages, so every query gets the whole feed on its first page, narrowed by title
+/// and description when searching.
+pub struct RssSource {
+    url: String,
+    #[cfg(not(target_arch = "wasm32"))]
+    agent: ureq::Agent,
+}
+
+impl RssSource {
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn new(url: &str, accept_invalid_certs: bool) -> Self {
+        RssSource {
+            url: url.trim().to_string(),
+            agent: http_agent(accept_invalid_certs),
+        }
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    pub fn new(url: &str) -> Self {
+        RssSource {
+            url: url.trim().to_string(),
+        }
+    }
+
+    fn url(&self) -> Result<&str, NewsApiError> {
+        if self.url.is_empty() {
+            return Err(NewsApiError::BadRequest("no feed URL set"));
+        }
+        url::Url::parse(&self.url)?;
+        Ok(&self.url)
+    }
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl ArticleSource for RssSource {
+    async fn fetch(&self, query: &Query) -> Result<Vec<NewsCardData>, NewsApiError> {
+        if query.page > 1 {
+            return Ok(Vec::new());
+        }
+        let url = self.url()?;
+        tracing::info!("fetching feed {}", url);
+        #[cfg(not(target_arch = "wasm32"))]
+        let xml = self.agent.get(url).call().map_err(Box::new)?.into_string()?;
+        #[cfg(target_arch = "wasm32")]
+        let xml = reqwasm::http::Request::get(url)
+            .abort_signal(query.signal.as_ref())
+            .send()
+            .await
+            .map_err(|_| NewsApiError::BadRequest("failed sending request"))?
+            .text()
+            .await
+            .map_err(|_| NewsApiError::BadRequest("failed reading the feed"))?;
+        parse_feed(&xml, query.search.as_deref())
+    }
+}
+
+/// The items of the RSS document `xml` that have a title and a link, as cards. Only
+/// those matching `search` are kept, if given.
+fn parse_feed(xml: &str, search: Option<&str>) -> Result<Vec<NewsCardData>, NewsApiError> {
+    let channel: rss::Channel = xml
+        .parse()
+        .map_err(|_| NewsApiError::BadRequest("not an RSS feed"))?;
+    let articles = channel
+        .items()
+        .iter()
+        .filter_map(|item| {
+            let title = decode_entities(item.title()?);
+            let description = item.description().map(decode_entities);
+            let fields = [title.as_str(), description.as_deref().unwrap_or_default()];
+            if !matches_filter(search.unwrap_or_default(), &fields) {
+                return None;
+            }
+            let mut news = NewsCardData::new(&title, item.link()?.trim());
+            news.source = channel.title().trim().to_string();
+            news.description = description;
+            news.author = item.author().map(decode_entities);
+            // RSS dates are RFC 2822, the feed sorts and compares RFC 3339 in UTC
+            news.published_at = item
+                .pub_date()
+                .and_then(|date| DateTime::parse_from_rfc2822(date.trim()).ok())
+                .map(|date| date.with_timezone(&Utc).to_rfc3339_opts(SecondsFormat::Secs, true));
+            Some(news)
+        })
+        .collect();
+    Ok(articles)
+}
+
+/// What the app tells newsapi it is.
+const USER_AGENT: &str = concat!("headlines/", env!("CARGO_PKG_VERSION"));
+
+/// A newsapi client that identifies itself as this app.
+pub(crate) fn newsapi_client(api_key: &str) -> NewsAPI {
+    let mut api = NewsAPI::new(api_key);
+    api.user_agent(USER_AGENT);
+    api
+}
+
+/// An agent for fetching images and article pages, with the TLS checks and User-Agent
+/// newsapi requests get.
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn http_agent(accept_invalid_certs: bool) -> ureq::Agent {
+    let mut api = newsapi_client("");
+    api.danger_accept_invalid_certs(accept_invalid_certs);
+    api.agent()
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SourceKind {
+    #[default]
+    NewsApi,
+    Demo,
+    /// The feed at `HeadlinesConfig::rss_url`.
+    Rss,
+}
+
+impl SourceKind {
+    pub const ALL: [SourceKind; 3] = [SourceKind::NewsApi, SourceKind::Demo, SourceKind::Rss];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            SourceKind::NewsApi => "newsapi.org",
+            SourceKind::Demo => "Demo",
+            SourceKind::Rss => "RSS",
+        }
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    pub fn source(&self, api_key: &str, rss_url: &str) -> BoxedSource {
+        match self {
+            SourceKind::NewsApi => Box::new(newsapi_client(api_key)),
+            SourceKind::Demo => Box::new(DemoSource),
+            SourceKind::Rss => Box::new(RssSource::new(rss_url)),
+        }
+    }
+
+    /// `accept_invalid_certs` is passed on to `NewsAPI::danger_accept_invalid_certs`.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn source(&self, api_key: &str, rss_url: &str, accept_invalid_certs: bool) -> BoxedSource {
+        match self {
+            SourceKind::NewsApi => {
+                let mut api = newsapi_client(api_key);
+                api.danger_accept_invalid_certs(accept_invalid_certs);
+                Box::new(api)
+            }
+            SourceKind::Demo => Box::new(DemoSource),
+            SourceKind::Rss => Box::new(RssSource::new(rss_url, accept_invalid_certs)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FEED: &str = r#"<?xml version="1.0"?>
+<rss version="2.0">
+  <channel>
+    <title> Example News </title>
+    <link>https://example.com</link>
+    <description>Example feed</description>
+    <item>
+      <title>Rust 2.0 &amp;amp; beyond</title>
+      <link> https://example.com/rust </link>
+      <description>What's next for Rust</description>
+      <pubDate>Mon, 01 May 2023 14:34:56 +0200</pubDate>
+    </item>
+    <item>
+      <title>Weather</title>
+      <link>https://example.com/weather</link>
+      <pubDate>yesterday</pubDate>
+    </item>
+    <item>
+      <description>An item without a title</description>
+      <link>https://example.com/untitled</link>
+    </item>
+  </channel>
+</rss>"#;
+
+    #[test]
+    fn feed_items_become_cards() {
+        let cards = parse_feed(FEED, None).unwrap();
+        assert_eq!(cards.len(), 2);
+        assert_eq!(cards[0].title, "Rust 2.0 & beyond");
+        assert_eq!(cards[0].url, "https://example.com/rust");
+        assert!(cards[0].url_valid);
+        assert_eq!(cards[0].source, "Example News");
+        assert_eq!(cards[0].description.as_deref(), Some("What's next for Rust"));
+        assert_eq!(cards[0].published_at.as_deref(), Some("2023-05-01T12:34:56Z"));
+        assert_eq!(cards[1].published_at, None);
+    }
+
+    #[test]
+    fn searches_filter_the_feed() {
+        let cards = parse_feed(FEED, Some("next for")).unwrap();
+        assert_eq!(cards.len(), 1);
+        assert_eq!(cards[0].title, "Rust 2.0 & beyond");
+        assert!(parse_feed(FEED, Some("sports")).unwrap().is_empty());
+    }
+
+    #[test]
+    fn anything_but_rss_is_an_error() {
+        let parsed = parse_feed("<html><body>Not a feed</body></html>", None);
+        assert!(matches!(parsed, Err(NewsApiError::BadRequest("not an RSS feed"))));
+    }
+
+    #[test]
+    fn the_feed_is_all_on_the_first_page() {
+        let source = RssSource::new("", false);
+        let mut query = Query::new(Category::General);
+        query.page = 2;
+        assert!(pollster::block_on(source.fetch(&query)).unwrap().is_empty());
+        query.page = 1;
+        let fetched = pollster::block_on(source.fetch(&query));
+        assert!(matches!(fetched, Err(NewsApiError::BadRequest("no feed URL set"))));
+    }
+}
@@ -0,0 +1,147 @@
+use std::sync::mpsc::{channel, Receiver};
+
+use eframe::egui::{popup_below_widget, Id, TextEdit, Ui};
+use newsapi::{NewsApiError, NewsSource};
+
+use crate::{
+    headlines::Headlines,
+    i18n::{t, tf},
+    source::newsapi_client,
+    Msg, SourceKind,
+};
+
+/// Suggestions shown below the filter at most.
+const MAX_SUGGESTIONS: usize = 8;
+
+/// newsapi's list of sources, fetched the first time the filter is focused.
+#[derive(Default)]
+pub struct SourceList {
+    sources: Vec<NewsSource>,
+    rx: Option<Receiver<Result<Vec<NewsSource>, NewsApiError>>>,
+    /// Set when the last fetch failed, so cards don't keep asking for the list again.
+    failed: bool,
+    /// The filter as last sent to the worker, so unchanged input doesn't refetch.
+    pub applied: Option<String>,
+}
+
+/// Trims the ids and drops empty ones, `None` when nothing is left.
+pub fn normalize_filter(filter: &str) -> Option<String> {
+    let ids: Vec<&str> = filter.split(',').map(str::trim).filter(|s| !s.is_empty()).collect();
+    (!ids.is_empty()).then(|| ids.join(","))
+}
+
+impl SourceList {
+    fn load(&mut self, api_key: &str) {
+        let (tx, rx) = channel();
+        self.rx = Some(rx);
+        self.failed = false;
+        let api = newsapi_client(api_key);
+        #[cfg(not(target_arch = "wasm32"))]
+        std::thread::spawn(move || {
+            let _ = tx.send(api.fetch_sources());
+        });
+        #[cfg(target_arch = "wasm32")]
+        wasm_bindgen_futures::spawn_local(async move {
+            let _ = tx.send(api.fetch_sources_web().await);
+        });
+    }
+
+    fn poll(&mut self) {
+        let result = match self.rx.as_ref().and_then(|rx| rx.try_recv().ok()) {
+            Some(result) => result,
+            None => return,
+        };
+        self.rx = None;
+        match result {
+            Ok(sources) => self.sources = sources,
+            Err(e) => {
+                tracing::warn!("failed fetching the sources list: {}", e);
+                self.failed = true;
+            }
+        }
+    }
+
+    /// Fetches the list for the card tags unless it's here, on its way or just failed.
+    pub(crate) fn ensure_loaded(&mut self, api_key: &str) {
+        self.poll();
+        if self.sources.is_empty() && self.rx.is_none() && !self.failed {
+            self.load(api_key);
+        }
+    }
+
+    /// "US · EN" for the source an article came from, `None` when it isn't in the list
+    /// or the list has neither code for it.
+    pub(crate) fn tag(&self, source_id: Option<&str>) -> Option<(String, String)> {
+        let source = self.sources.iter().find(|s| Some(s.id()) == source_id)?;
+        let codes: Vec<String> = [source.country(), source.language()]
+            .iter()
+            .filter(|code| !code.is_empty())
+            .map(|code| code.to_uppercase())
+            .collect();
+        if codes.is_empty() {
+            return None;
+        }
+        let hint = tf("source_tag_hint", &[&source.name(), &codes.join(", ")]);
+        Some((codes.join(" · "), hint))
+    }
+
+    /// Sources whose id or name contains `typed`, case-insensitively.
+    fn suggestions(&self, typed: &str) -> Vec<&NewsSource> {
+        let typed = typed.to_lowercase();
+        self.sources
+            .iter()
+            .filter(|s| s.id().contains(&typed) || s.name().to_lowercase().contains(&typed))
+            .take(MAX_SUGGESTIONS)
+            .collect()
+    }
+}
+
+impl Headlines {
+    /// Limits the feed to a comma separated list of source ids, suggesting valid ids for
+    /// the one being typed.
+    pub fn render_source_filter(&mut self, ui: &mut Ui) {
+        let list = &mut self.sources_list;
+        list.poll();
+
+        let edit_id = Id::new("source_filter");
+        let response = ui.add(
+            TextEdit::singleline(&mut self.config.source_filter)
+                .id(edit_id)
+                .hint_text(t("source_filter_hint")),
+        );
+        let newsapi = self.config.source == SourceKind::NewsApi;
+        if response.gained_focus() && newsapi && list.sources.is_empty() && list.rx.is_none() {
+            list.load(&self.config.api_key);
+        }
+
+        let (done, typed) = match self.config.source_filter.rsplit_once(',') {
+            Some((done, typed)) => (format!("{},", done), typed.trim().to_string()),
+            None => (String::new(), self.config.source_filter.trim().to_string()),
+        };
+        let suggestions = if typed.is_empty() { Vec::new() } else { list.suggestions(&typed) };
+        let popup_id = edit_id.with("suggestions");
+        if response.has_focus() && !suggestions.is_empty() {
+            ui.memory().open_popup(popup_id);
+        }
+        let mut picked = None;
+        popup_below_widget(ui, popup_id, &response, |ui| {
+            for source in &suggestions {
+                let label = format!("{} ({})", source.name(), source.id());
+                if ui.selectable_label(false, label).clicked() {
+                    picked = Some(source.id().to_string());
+                }
+            }
+        });
+        if let Some(id) = &picked {
+            self.config.source_filter = format!("{}{}, ", done, id);
+            ui.memory().request_focus(edit_id);
+        }
+
+        let filter = normalize_filter(&self.config.source_filter);
+        if (response.lost_focus() || picked.is_some()) && filter != self.sources_list.applied {
+            self.sources_list.applied = filter.clone();
+            self.reset_feed();
+            self.send(Msg::SourceFilterChanged(filter));
+        }
+    }
+}
@@ -0,0 +1,216 @@
+use std::sync::mpsc::{sync_channel, Receiver};
+
+use eframe::egui::{
+    ComboBox, Context, Hyperlink, Id, Key, Label, RichText, ScrollArea, Separator, SidePanel,
+    TextEdit, Ui,
+};
+use newsapi::{Category, Country};
+
+use crate::{
+    headlines::{page_size, Fetcher, Headlines, RED},
+    i18n::t,
+    icons::{icon, plain},
+    BoxedSource, FetchEvent, NewsCardData, PostProcess, Query, SourceKind, PADDING,
+};
+
+/// A feed shown beside the main one, with its own query, articles and scroll.
+pub struct FeedPane {
+    /// Position among the panes, which keeps the ids of their widgets apart.
+    index: usize,
+    settings: PaneSettings,
+    query: Query,
+    search: String,
+    articles: Vec<NewsCardData>,
+    error: Option<String>,
+    fetcher: Fetcher,
+    rx: Receiver<FetchEvent>,
+}
+
+impl FeedPane {
+    fn new(
+        index: usize,
+        settings: PaneSettings,
+        query: Query,
+        capacity: usize,
+        ctx: Context,
+    ) -> Self {
+        let (tx, rx) = sync_channel(capacity.max(1));
+        let mut pane = FeedPane {
+            index,
+            fetcher: Fetcher::new(settings.source(), tx, ctx),
+            settings,
+            query,
+            search: String::new(),
+            articles: Vec::new(),
+            error: None,
+            rx,
+        };
+        pane.fetch();
+        pane
+    }
+
+    /// Switches to a source built from `settings` and fetches the feed from it again.
+    fn change_settings(&mut self, settings: PaneSettings) {
+        self.fetcher.set_source(settings.source());
+        self.query.country = settings.country;
+        self.query.page_size = page_size(settings.low_bandwidth);
+        self.settings = settings;
+        self.fetch();
+    }
+
+    fn fetch(&mut self) {
+        self.articles.clear();
+        self.error = None;
+        self.fetcher.spawn(self.query.clone());
+    }
+
+    fn poll(&mut self, post_processor: &dyn PostProcess) {
+        while let Ok(event) = self.rx.try_recv() {
+            match event {
+                FetchEvent::Started { .. } => self.articles.clear(),
+                FetchEvent::Article(a) => {
+                    let mut new = vec![*a];
+                    post_processor.post_process(&mut new);
+                    self.articles.append(&mut new);
+                }
+                // a refresh keeps what's shown, a new query starts out empty
+                FetchEvent::NotModified { mut articles, .. } if self.articles.is_empty() => {
+                    post_processor.post_process(&mut articles);
+                    self.articles = articles;
+                }
+                FetchEvent::NextPage(_)
+                | FetchEvent::CategoriesFailed(_)
+                | FetchEvent::NotModified { .. }
+                | FetchEvent::Done => {}
+                FetchEvent::Error(e) => self.error = Some(e.to_string()),
+            }
+        }
+    }
+
+    fn render(&mut self, ui: &mut Ui) -> Category {
+        let mut category = self.query.category;
+        ui.horizontal(|ui| {
+            ComboBox::from_id_source(("split_category", self.index))
+                .selected_text(category.name())
+                .show_ui(ui, |ui| {
+                    for c in Category::ALL {
+                        ui.selectable_value(&mut category, c, c.name());
+                    }
+                });
+            let search = ui.add(TextEdit::singleline(&mut self.search).hint_text(t("search")));
+            if search.lost_focus() && ui.input().key_pressed(Key::Enter) {
+                let search = self.search.trim();
+                self.query.search = (!search.is_empty()).then(|| search.to_string());
+                self.fetch();
+            }
+            if ui.small_button(icon("🔄")).on_hover_text(t("refresh_now")).clicked() {
+                // `Started` replaces the articles, an unchanged feed keeps them
+                self.error = None;
+                self.fetcher.spawn(self.query.clone());
+            }
+        });
+        if category != self.query.category {
+            self.query.category = category;
+            self.fetch();
+        }
+        ui.add(Separator::default());
+
+        if let Some(e) = &self.error {
+            ui.colored_label(RED, e);
+        } else if self.articles.is_empty() {
+            ui.label(plain(t("loading")));
+        }
+        ScrollArea::vertical().id_source(("split_scroll", self.index)).show(ui, |ui| {
+            for a in &self.articles {
+                ui.add_space(PADDING);
+                if a.url_valid {
+                    ui.add(Hyperlink::from_label_and_url(RichText::new(&a.title).strong(), &a.url));
+                } else {
+                    ui.add(Label::new(RichText::new(&a.title).strong()).wrap(true));
+                }
+                if !a.source.is_empty() {
+                    ui.label(RichText::new(&a.source).small().weak());
+                }
+                if let Some(description) = &a.description {
+                    ui.label(description);
+                }
+                ui.add_space(PADDING);
+                ui.add(Separator::default());
+            }
+        });
+        category
+    }
+}
+
+/// What a pane's source is built from. When any of it changes in settings the pane
+/// fetches again from a new source.
+#[derive(Clone, PartialEq)]
+struct PaneSettings {
+    source: SourceKind,
+    api_key: String,
+    rss_url: String,
+    country: Country,
+    low_bandwidth: bool,
+    #[cfg(not(target_arch = "wasm32"))]
+    accept_invalid_certs: bool,
+}
+
+impl PaneSettings {
+    #[cfg(not(target_arch = "wasm32"))]
+    fn source(&self) -> BoxedSource {
+        self.source.source(&self.api_key, &self.rss_url, self.accept_invalid_certs)
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn source(&self) -> BoxedSource {
+        self.source.source(&self.api_key, &self.rss_url)
+    }
+}
+
+impl Headlines {
+    /// The extra feeds, opened on the right of the main one while split view is on.
+    /// Has to be drawn before the central panel it shares the window with.
+    pub fn render_split_pane(&mut self, ctx: &Context) {
+        if !self.config.split_view {
+            self.split.clear();
+            return;
+        }
+        let settings = self.pane_settings();
+        if self.split.is_empty() {
+            let mut query = Query::new(self.config.split_category);
+            query.country = settings.country;
+            query.page_size = page_size(settings.low_bandwidth);
+            let capacity = self.config.news_channel_capacity;
+            self.split.push(FeedPane::new(0, settings.clone(), query, capacity, ctx.clone()));
+        }
+        let width = ctx.input().screen_rect().width() / 2.0 / self.split.len() as f32;
+        for pane in &mut self.split {
+            if pane.settings != settings {
+                pane.change_settings(settings.clone());
+            }
+            pane.poll(&*self.post_processor);
+            SidePanel::right(Id::new("split_pane").with(pane.index))
+                .resizable(true)
+                .default_width(width)
+                .show(ctx, |ui| {
+                    let category = pane.render(ui);
+                    // only the first pane's category is remembered
+                    if pane.index == 0 {
+                        self.config.split_category = category;
+                    }
+                });
+        }
+    }
+
+    fn pane_settings(&self) -> PaneSettings {
+        PaneSettings {
+            source: self.config.source,
+            api_key: self.config.api_key.to_string(),
+            rss_url: self.config.rss_url.clone(),
+            country: self.config.country.unwrap_or_default(),
+            low_bandwidth: self.config.low_bandwidth,
+            #[cfg(not(target_arch = "wasm32"))]
+            accept_invalid_certs: self.config.danger_accept_invalid_certs,
+        }
+    }
+}
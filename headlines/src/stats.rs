@@ -0,0 +1,77 @@
+use eframe::egui::{Context, Grid, Id, Window};
+use newsapi::Category;
+use serde::{Deserialize, Serialize};
+
+use crate::{headlines::Headlines, i18n::t, numbers::format_count};
+
+/// Reading habits counted on this device only, nothing here is ever sent anywhere.
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ReadingStats {
+    /// Nothing is counted until the user opts in.
+    pub enabled: bool,
+    pub articles_opened: u64,
+    pub searches_run: u64,
+    pub categories_viewed: Vec<(Category, u64)>,
+}
+
+impl ReadingStats {
+    pub fn article_opened(&mut self) {
+        if self.enabled {
+            self.articles_opened += 1;
+        }
+    }
+
+    pub fn search_run(&mut self) {
+        if self.enabled {
+            self.searches_run += 1;
+        }
+    }
+
+    pub fn category_viewed(&mut self, category: Category) {
+        if !self.enabled {
+            return;
+        }
+        match self.categories_viewed.iter_mut().find(|(c, _)| *c == category) {
+            Some((_, count)) => *count += 1,
+            None => self.categories_viewed.push((category, 1)),
+        }
+    }
+
+    pub fn reset(&mut self) {
+        *self = ReadingStats {
+            enabled: self.enabled,
+            ..Default::default()
+        };
+    }
+}
+
+impl Headlines {
+    pub fn render_stats(&mut self, ctx: &Context) {
+        let mut open = self.show_stats;
+        Window::new(t("reading_stats")).id(Id::new("stats")).open(&mut open).show(ctx, |ui| {
+            let stats = &mut self.config.stats;
+            ui.checkbox(&mut stats.enabled, t("keep_stats"))
+                .on_hover_text(t("keep_stats_hint"));
+            Grid::new("stats_grid").num_columns(2).show(ui, |ui| {
+                ui.label(t("articles_opened"));
+                ui.label(format_count(stats.articles_opened));
+                ui.end_row();
+                ui.label(t("searches_run"));
+                ui.label(format_count(stats.searches_run));
+                ui.end_row();
+                let mut categories = stats.categories_viewed.clone();
+                categories.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+                for (category, count) in categories {
+                    ui.label(category.name());
+                    ui.label(format_count(count));
+                    ui.end_row();
+                }
+            });
+            if ui.button(t("reset_stats")).clicked() {
+                stats.reset();
+            }
+        });
+        self.show_stats = open;
+    }
+}
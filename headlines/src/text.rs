@@ -0,0 +1,366 @@
+use std::ops::Range;
+
+use url::Url;
+
+/// Decodes the HTML entities newsapi leaves in titles and descriptions,
+/// e.g. `&amp;`, `&quot;`, `&#39;` and `&#x2019;`. Unknown entities are kept as is.
+pub fn decode_entities(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(start) = rest.find('&') {
+        out.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let decoded = rest
+            .find(';')
+            .filter(|&end| end <= 10)
+            .and_then(|end| decode_entity(&rest[1..end]).map(|c| (c, end)));
+        match decoded {
+            Some((c, end)) => {
+                out.push(c);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+fn decode_entity(entity: &str) -> Option<char> {
+    if let Some(hex) = entity.strip_prefix("#x").or_else(|| entity.strip_prefix("#X")) {
+        return u32::from_str_radix(hex, 16).ok().and_then(char::from_u32);
+    }
+    if let Some(dec) = entity.strip_prefix('#') {
+        return dec.parse().ok().and_then(char::from_u32);
+    }
+    match entity {
+        "amp" => Some('&'),
+        "lt" => Some('<'),
+        "gt" => Some('>'),
+        "quot" => Some('"'),
+        "apos" => Some('\''),
+        "nbsp" => Some('\u{a0}'),
+        "hellip" => Some('…'),
+        "mdash" => Some('—'),
+        "ndash" => Some('–'),
+        "lsquo" => Some('‘'),
+        "rsquo" => Some('’'),
+        "ldquo" => Some('“'),
+        "rdquo" => Some('”'),
+        _ => None,
+    }
+}
+
+/// Shortens `s` to at most `max_chars` characters, cutting on a word boundary and
+/// appending an ellipsis. Returns `None` when `s` already fits or `max_chars` is 0.
+pub fn truncate_words(s: &str, max_chars: usize) -> Option<String> {
+    if max_chars == 0 || s.chars().count() <= max_chars {
+        return None;
+    }
+    let cut = s.char_indices().nth(max_chars).map_or(s.len(), |(i, _)| i);
+    let head = &s[..cut];
+    // keep the whole head if the cut happens to fall between two words
+    let head = if s[cut..].starts_with(char::is_whitespace) {
+        head
+    } else {
+        head.rfind(char::is_whitespace).map_or(head, |i| &head[..i])
+    };
+    Some(format!("{}…", head.trim_end()))
+}
+
+/// Splits newsapi's truncation marker off `content`, e.g.
+/// `"Some text… [+1234 chars]"` -> `("Some text…", Some(1234))`.
+pub fn split_content_marker(s: &str) -> (&str, Option<usize>) {
+    let trimmed = s.trim_end();
+    let marker = trimmed
+        .strip_suffix(" chars]")
+        .and_then(|rest| rest.rfind("[+").map(|i| (i, &rest[i + 2..])));
+    match marker {
+        Some((start, count)) => match count.parse() {
+            Ok(count) => (trimmed[..start].trim_end(), Some(count)),
+            Err(_) => (s, None),
+        },
+        None => (s, None),
+    }
+}
+
+/// Query parameters that only track where a click came from and don't change
+/// which article a url points to.
+const TRACKING_PARAMS: [&str; 9] = [
+    "fbclid", "gclid", "dclid", "mc_cid", "mc_eid", "cmpid", "ref", "ref_src", "ocid",
+];
+
+/// Normalizes an article url into a stable identity: lowercases the host and drops
+/// the fragment, `utm_*` and other tracking parameters. Unparseable urls are used as is.
+pub fn article_id(url: &str) -> String {
+    let mut parsed = match Url::parse(url.trim()) {
+        Ok(parsed) => parsed,
+        Err(_) => return url.trim().to_string(),
+    };
+    parsed.set_fragment(None);
+    let kept: Vec<(String, String)> = parsed
+        .query_pairs()
+        .filter(|(k, _)| {
+            let k = k.to_ascii_lowercase();
+            !k.starts_with("utm_") && !TRACKING_PARAMS.contains(&k.as_str())
+        })
+        .map(|(k, v)| (k.into_owned(), v.into_owned()))
+        .collect();
+    if kept.is_empty() {
+        parsed.set_query(None);
+    } else {
+        parsed.query_pairs_mut().clear().extend_pairs(kept);
+    }
+    let path = parsed.path().trim_end_matches('/').to_string();
+    if !path.is_empty() {
+        parsed.set_path(&path);
+    }
+    // `Url` already lowercases the host of http(s) urls
+    parsed.to_string()
+}
+
+/// Whether `url` is an absolute http(s) url a hyperlink can actually open.
+pub fn is_web_url(url: &str) -> bool {
+    match Url::parse(url.trim()) {
+        Ok(parsed) => matches!(parsed.scheme(), "http" | "https") && parsed.has_host(),
+        Err(_) => false,
+    }
+}
+
+/// Case-insensitive substring match of `query` against any of `fields`.
+/// An empty query matches everything.
+pub fn matches_filter(query: &str, fields: &[&str]) -> bool {
+    let query = query.trim().to_lowercase();
+    query.is_empty() || fields.iter().any(|f| f.to_lowercase().contains(&query))
+}
+
+/// `title` without a trailing `" - {source}"` or `" | {source}"`, which newsapi titles
+/// often carry on top of the source they're shown with. Unknown sources change nothing.
+pub fn strip_source_suffix(title: &str, source: &str) -> String {
+    let (title, source) = (title.trim_end(), source.trim());
+    if source.is_empty() {
+        return title.to_string();
+    }
+    let head = title
+        .len()
+        .checked_sub(source.len())
+        .filter(|&cut| title.get(cut..).is_some_and(|end| end.eq_ignore_ascii_case(source)))
+        .map(|cut| &title[..cut]);
+    let stripped = [" - ", " | "]
+        .iter()
+        .find_map(|sep| head.and_then(|head| head.strip_suffix(sep)))
+        .map(str::trim_end);
+    match stripped {
+        Some(stripped) if !stripped.is_empty() => stripped.to_string(),
+        _ => title.to_string(),
+    }
+}
+
+/// The terms of a comma separated watchlist, e.g. `"Apple, Tesla"`.
+pub fn watch_terms(watchlist: &str) -> Vec<&str> {
+    watchlist.split(',').map(str::trim).filter(|t| !t.is_empty()).collect()
+}
+
+/// Byte ranges of `text` holding one of `terms` as a whole word, ignoring ASCII case.
+/// Sorted, with overlapping matches merged.
+pub fn watch_matches(text: &str, terms: &[&str]) -> Vec<Range<usize>> {
+    let haystack = text.to_ascii_lowercase();
+    let is_word = |c: Option<char>| c.is_some_and(char::is_alphanumeric);
+    let mut ranges = Vec::new();
+    for term in terms {
+        for (start, found) in haystack.match_indices(&term.to_ascii_lowercase()) {
+            let end = start + found.len();
+            if !is_word(text[..start].chars().next_back()) && !is_word(text[end..].chars().next()) {
+                ranges.push(start..end);
+            }
+        }
+    }
+    ranges.sort_by_key(|r: &Range<usize>| r.start);
+    let mut merged: Vec<Range<usize>> = Vec::with_capacity(ranges.len());
+    for r in ranges {
+        match merged.last_mut() {
+            Some(last) if r.start <= last.end => last.end = last.end.max(r.end),
+            _ => merged.push(r),
+        }
+    }
+    merged
+}
+
+/// Lowercases `title` and drops punctuation, collapsing the words to single spaces, so
+/// `"Stocks Rally -- Again!"` and `"stocks rally again"` compare equal.
+pub fn normalize_title(title: &str) -> String {
+    let cleaned: String = title
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { ' ' })
+        .flat_map(char::to_lowercase)
+        .collect();
+    cleaned.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Edit distance between `a` and `b`, counted in chars.
+pub fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let above = row[j + 1];
+            let substitution = diagonal + usize::from(ca != *cb);
+            row[j + 1] = substitution.min(above + 1).min(row[j] + 1);
+            diagonal = above;
+        }
+    }
+    row[b.len()]
+}
+
+/// Share of the longer title that may differ for two normalized titles to count as
+/// the same story.
+const SIMILAR_TITLE_RATIO: f32 = 0.1;
+
+/// Whether two already normalized titles are close enough to be the same story.
+pub fn similar_titles(a: &str, b: &str) -> bool {
+    let longest = a.chars().count().max(b.chars().count());
+    let allowed = (longest as f32 * SIMILAR_TITLE_RATIO) as usize;
+    // the length difference alone is a lower bound of the distance
+    a.chars().count().abs_diff(b.chars().count()) <= allowed && levenshtein(a, b) <= allowed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn entities_are_decoded() {
+        let title = "Q&amp;A: Don&#39;t say &quot;never&quot; &#x2014; it&#X2019;s early";
+        assert_eq!(decode_entities(title), "Q&A: Don't say \"never\" — it’s early");
+    }
+
+    #[test]
+    fn unknown_entities_and_bare_ampersands_are_kept() {
+        assert_eq!(decode_entities("Tom & Jerry &bogus; &#xZZ;"), "Tom & Jerry &bogus; &#xZZ;");
+    }
+
+    #[test]
+    fn content_marker_is_split_off() {
+        let content = "Markets fell sharply on Monday as… [+1234 chars]";
+        let split = ("Markets fell sharply on Monday as…", Some(1234));
+        assert_eq!(split_content_marker(content), split);
+        assert_eq!(split_content_marker("Fell sharply [+5 chars]  "), ("Fell sharply", Some(5)));
+    }
+
+    #[test]
+    fn content_without_a_marker_is_kept() {
+        assert_eq!(split_content_marker("The whole story."), ("The whole story.", None));
+        assert_eq!(split_content_marker(""), ("", None));
+    }
+
+    #[test]
+    fn malformed_markers_are_kept() {
+        for content in ["Text [+many chars]", "Text [+12 characters]", "Text +12 chars]"] {
+            assert_eq!(split_content_marker(content), (content, None));
+        }
+    }
+
+    #[test]
+    fn urls_of_the_same_article_share_an_id() {
+        let id = article_id("https://example.com/news/story");
+        for url in [
+            "https://example.com/news/story?utm_source=twitter&utm_medium=social",
+            "https://example.com/news/story?fbclid=abc123&ref=homepage",
+            "https://example.com/news/story/",
+            "https://example.com/news/story#comments",
+            "HTTPS://Example.COM/news/story",
+            "  https://example.com/news/story  ",
+        ] {
+            assert_eq!(article_id(url), id, "{}", url);
+        }
+        let kept = article_id("https://example.com/story?id=7&utm_campaign=spring");
+        assert_eq!(kept, article_id("https://example.com/story?id=7"));
+    }
+
+    #[test]
+    fn urls_of_different_articles_get_different_ids() {
+        let id = article_id("https://example.com/news/story");
+        for url in [
+            "https://example.com/news/other-story",
+            "https://example.com/news/Story",
+            "https://example.com/news/story?id=2",
+            "https://example.org/news/story",
+            "http://example.com/news/story",
+        ] {
+            assert_ne!(article_id(url), id, "{}", url);
+        }
+    }
+
+    #[test]
+    fn only_absolute_http_urls_are_web_urls() {
+        assert!(is_web_url("https://example.com/story"));
+        assert!(is_web_url(" http://example.com "));
+        for url in ["", "not a url", "example.com/story", "/news/story", "https://", "http://"] {
+            assert!(!is_web_url(url), "{:?}", url);
+        }
+        for url in ["javascript:alert(1)", "mailto:news@example.com", "ftp://example.com/x"] {
+            assert!(!is_web_url(url), "{:?}", url);
+        }
+    }
+
+    #[test]
+    fn titles_are_normalized() {
+        assert_eq!(normalize_title("Stocks Rally -- Again!"), "stocks rally again");
+        assert_eq!(normalize_title("  Ünïcode’s   TITLE?! "), "ünïcode s title");
+        assert_eq!(normalize_title("..."), "");
+    }
+
+    #[test]
+    fn levenshtein_counts_chars() {
+        assert_eq!(levenshtein("", ""), 0);
+        assert_eq!(levenshtein("abc", ""), 3);
+        assert_eq!(levenshtein("", "abc"), 3);
+        assert_eq!(levenshtein("kitten", "sitting"), 3);
+        assert_eq!(levenshtein("flaw", "lawn"), 2);
+        assert_eq!(levenshtein("café", "cafe"), 1);
+    }
+
+    #[test]
+    fn similar_titles_allow_a_tenth_to_differ() {
+        // 40 chars long, so up to 4 edits count as the same story
+        let title = "central bank raises interest rates again";
+        assert!(similar_titles(title, title));
+        assert!(similar_titles(title, "central bank raises interest rate again"));
+        assert!(similar_titles(title, "central bank raised interest rates again"));
+        assert!(similar_titles(title, &title[..36]));
+        assert!(!similar_titles(title, &title[..35]));
+        assert!(!similar_titles(title, "central bank cuts interest rates again"));
+        // too short for any edit to be allowed
+        assert!(!similar_titles("war", "car"));
+    }
+
+    #[test]
+    fn source_suffixes_are_stripped() {
+        assert_eq!(strip_source_suffix("Rates rise - Reuters", "Reuters"), "Rates rise");
+        assert_eq!(strip_source_suffix("Rates rise | Reuters", "Reuters"), "Rates rise");
+        assert_eq!(strip_source_suffix("Rates rise - reuters ", " Reuters"), "Rates rise");
+    }
+
+    #[test]
+    fn titles_without_the_source_suffix_are_kept() {
+        assert_eq!(strip_source_suffix("Rates rise", "Reuters"), "Rates rise");
+        assert_eq!(strip_source_suffix("Rates rise - AP", "Reuters"), "Rates rise - AP");
+        assert_eq!(strip_source_suffix("Rates rise - Reuters", ""), "Rates rise - Reuters");
+        // only a separated suffix counts, the title itself is never emptied
+        assert_eq!(strip_source_suffix("Rates rise Reuters", "Reuters"), "Rates rise Reuters");
+        assert_eq!(strip_source_suffix(" - Reuters", "Reuters"), " - Reuters");
+    }
+
+    #[test]
+    fn the_source_in_mid_title_is_kept() {
+        let title = "Reuters - Rates rise - Bloomberg";
+        assert_eq!(strip_source_suffix(title, "Reuters"), title);
+        let title = "Why Reuters | AP reporters agree";
+        assert_eq!(strip_source_suffix(title, "Reuters"), title);
+    }
+}
@@ -0,0 +1,16 @@
+/// Backend used to translate article titles on the news cards.
+///
+/// Implement this to plug in a real translation service; returning `None`
+/// means the text couldn't be translated and the card keeps the original.
+pub trait Translator {
+    fn translate(&self, text: &str) -> Option<String>;
+}
+
+/// Default translator used when no translation service is configured.
+pub struct NoopTranslator;
+
+impl Translator for NoopTranslator {
+    fn translate(&self, _text: &str) -> Option<String> {
+        None
+    }
+}
@@ -0,0 +1,71 @@
+#[cfg(not(target_arch = "wasm32"))]
+use std::{
+    sync::{Arc, Condvar, Mutex},
+    thread,
+    time::{Duration, Instant},
+};
+
+use eframe::egui::Context;
+
+/// The deadline the timer thread waits for, and the signal that it changed.
+#[cfg(not(target_arch = "wasm32"))]
+type Deadline = Arc<(Mutex<Option<Instant>>, Condvar)>;
+
+/// Wakes the UI at a deadline, for timers that have to fire while no frames are drawn.
+/// egui can't schedule a repaint itself yet, so natively one thread waits for whichever
+/// deadline was set last, and on the web the last browser timeout is replaced.
+#[derive(Default)]
+pub struct Waker {
+    #[cfg(not(target_arch = "wasm32"))]
+    deadline: Option<Deadline>,
+    #[cfg(target_arch = "wasm32")]
+    timeout: Option<gloo_timers::callback::Timeout>,
+}
+
+impl Waker {
+    /// Repaints `ctx` after `secs`, instead of at the deadline set before.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn wake_after(&mut self, ctx: &Context, secs: f64) {
+        let at = Instant::now() + Duration::from_secs_f64(secs.max(0.0));
+        let deadline = self.deadline.get_or_insert_with(|| spawn_timer(ctx.clone()));
+        let (next, changed) = &**deadline;
+        *next.lock().unwrap_or_else(|e| e.into_inner()) = Some(at);
+        changed.notify_one();
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    pub fn wake_after(&mut self, ctx: &Context, secs: f64) {
+        let millis = (secs.max(0.0) * 1000.0) as u32;
+        let ctx = ctx.clone();
+        // dropping the previous timeout cancels it
+        self.timeout = Some(gloo_timers::callback::Timeout::new(millis, move || {
+            ctx.request_repaint()
+        }));
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn spawn_timer(ctx: Context) -> Deadline {
+    let deadline: Deadline = Arc::default();
+    let timer = deadline.clone();
+    thread::spawn(move || {
+        let (next, changed) = &*timer;
+        let mut next_at = next.lock().unwrap_or_else(|e| e.into_inner());
+        loop {
+            next_at = match *next_at {
+                None => changed.wait(next_at).unwrap_or_else(|e| e.into_inner()),
+                Some(at) if Instant::now() >= at => {
+                    *next_at = None;
+                    ctx.request_repaint();
+                    next_at
+                }
+                Some(at) => {
+                    let wait = at.saturating_duration_since(Instant::now());
+                    let waited = changed.wait_timeout(next_at, wait);
+                    waited.unwrap_or_else(|e| e.into_inner()).0
+                }
+            };
+        }
+    });
+    deadline
+}
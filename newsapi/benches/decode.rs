@@ -0,0 +1,103 @@
+// Decoding a full `pageSize=100` page the ways responses used to be decoded (every
+// article through a `serde_json::Value` tree, then an untagged enum, which buffers every
+// article in serde's own tree before trying it as an `Article`) and the way
+// `NewsAPIResponse` reads them now, field by field off the body.
+// It runs on the fetch worker, so this is time the feed waits, not a UI frame.
+// There's no bench framework among the dependencies, so this times a fixed number of
+// runs itself: `cargo bench -p newsapi`.
+
+use std::time::{Duration, Instant};
+
+use newsapi::{Article, NewsAPIResponse};
+
+const ARTICLES: usize = 100;
+const RUNS: u32 = 1000;
+
+/// A page of `ARTICLES` articles with content fields about as long as newsapi sends.
+fn payload() -> String {
+    let content = "Lorem ipsum dolor sit amet, consectetur adipiscing elit. ".repeat(40);
+    let articles: Vec<serde_json::Value> = (0..ARTICLES)
+        .map(|i| {
+            serde_json::json!({
+                "source": { "id": "bbc-news", "name": "BBC News" },
+                "author": "A. Reporter",
+                "title": format!("Headline number {} - BBC News", i),
+                "description": content[..300].to_string(),
+                "url": format!("https://www.bbc.co.uk/news/article-{}", i),
+                "urlToImage": format!("https://ichef.bbci.co.uk/images/{}.jpg", i),
+                "publishedAt": "2023-05-01T12:34:56Z",
+                "content": content,
+            })
+        })
+        .collect();
+    serde_json::json!({ "status": "ok", "totalResults": ARTICLES, "articles": articles })
+        .to_string()
+}
+
+#[derive(serde::Deserialize)]
+struct ValueResponse {
+    articles: Vec<serde_json::Value>,
+}
+
+fn via_value(body: &str) -> usize {
+    let raw: ValueResponse = serde_json::from_str(body).unwrap();
+    let articles: Vec<Article> = raw
+        .articles
+        .into_iter()
+        .filter_map(|value| serde_json::from_value(value).ok())
+        .collect();
+    articles.len()
+}
+
+#[derive(serde::Deserialize)]
+#[serde(untagged)]
+enum UntaggedArticle {
+    Article(Article),
+    Malformed(serde_json::Value),
+}
+
+#[derive(serde::Deserialize)]
+struct UntaggedResponse {
+    articles: Vec<UntaggedArticle>,
+}
+
+fn via_untagged(body: &str) -> usize {
+    let raw: UntaggedResponse = serde_json::from_str(body).unwrap();
+    let articles: Vec<Article> = raw
+        .articles
+        .into_iter()
+        .filter_map(|raw| match raw {
+            UntaggedArticle::Article(article) => Some(article),
+            UntaggedArticle::Malformed(value) => serde_json::from_value(value).ok(),
+        })
+        .collect();
+    articles.len()
+}
+
+fn via_response(body: &str) -> usize {
+    let response: NewsAPIResponse = serde_json::from_str(body).unwrap();
+    response.articles().len()
+}
+
+fn time(name: &str, body: &str, decode: fn(&str) -> usize) -> Duration {
+    assert_eq!(decode(body), ARTICLES);
+    let started = Instant::now();
+    for _ in 0..RUNS {
+        std::hint::black_box(decode(std::hint::black_box(body)));
+    }
+    let per_run = started.elapsed() / RUNS;
+    println!("{:<15} {:>10.1?} per page", name, per_run);
+    per_run
+}
+
+fn main() {
+    let body = payload();
+    println!("{} articles, {} KiB", ARTICLES, body.len() / 1024);
+    let value = time("value tree", &body, via_value);
+    let untagged = time("untagged enum", &body, via_untagged);
+    let direct = time("field by field", &body, via_response);
+    for (name, before) in [("value tree", value), ("untagged enum", untagged)] {
+        let speedup = before.as_secs_f64() / direct.as_secs_f64();
+        println!("{:.1}x faster than the {}", speedup, name);
+    }
+}
@@ -0,0 +1,402 @@
+//! Decoding newsapi responses straight off the body. Articles are handed over one at a
+//! time as they're read, and a malformed one only rejects itself: every field is read
+//! into whatever type it turns out to have, so nothing is buffered to retry it with.
+
+use std::fmt;
+
+use serde::{
+    de::{DeserializeSeed, Error, IgnoredAny, MapAccess, SeqAccess, Visitor},
+    Deserialize, Deserializer,
+};
+
+use crate::{Article, NewsAPIResponse, Source};
+
+/// Decodes a whole response, the articles go to `each` instead of into it. `started` is
+/// called with `totalResults` once the articles begin, newsapi sends it ahead of them.
+pub(crate) struct ResponseSeed<'a> {
+    pub started: &'a mut dyn FnMut(u64),
+    pub each: &'a mut dyn FnMut(Article),
+}
+
+impl<'de> Deserialize<'de> for NewsAPIResponse {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let mut articles = Vec::new();
+        let seed = ResponseSeed {
+            started: &mut |_| {},
+            each: &mut |article| articles.push(article),
+        };
+        let mut response = seed.deserialize(deserializer)?;
+        response.articles = articles;
+        Ok(response)
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(field_identifier, rename_all = "camelCase")]
+enum ResponseField {
+    Status,
+    Code,
+    TotalResults,
+    Articles,
+    #[serde(other)]
+    Other,
+}
+
+impl<'de> DeserializeSeed<'de> for ResponseSeed<'_> {
+    type Value = NewsAPIResponse;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'de> Visitor<'de> for ResponseSeed<'_> {
+    type Value = NewsAPIResponse;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a newsapi response")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut status = None;
+        let mut code = None;
+        let mut total_results = 0;
+        let mut skipped = 0;
+        while let Some(field) = map.next_key()? {
+            match field {
+                ResponseField::Status => status = Some(map.next_value()?),
+                ResponseField::Code => code = map.next_value()?,
+                ResponseField::TotalResults => total_results = map.next_value()?,
+                ResponseField::Articles => {
+                    (self.started)(total_results);
+                    let each = &mut *self.each;
+                    map.next_value_seed(ArticleSeq {
+                        each,
+                        skipped: &mut skipped,
+                    })?;
+                }
+                ResponseField::Other => {
+                    map.next_value::<IgnoredAny>()?;
+                }
+            }
+        }
+        Ok(NewsAPIResponse {
+            status: status.ok_or_else(|| Error::missing_field("status"))?,
+            articles: Vec::new(),
+            code,
+            total_results,
+            skipped,
+            not_modified: false,
+        })
+    }
+}
+
+/// The `articles` list, counting the malformed ones it leaves out.
+struct ArticleSeq<'a> {
+    each: &'a mut dyn FnMut(Article),
+    skipped: &'a mut usize,
+}
+
+impl<'de> DeserializeSeed<'de> for ArticleSeq<'_> {
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        deserializer.deserialize_seq(self)
+    }
+}
+
+impl<'de> Visitor<'de> for ArticleSeq<'_> {
+    type Value = ();
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a list of articles")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<(), A::Error> {
+        while let Some(RawArticle(article)) = seq.next_element()? {
+            match article {
+                Ok(article) => (self.each)(article),
+                Err(e) => {
+                    tracing::warn!("skipping malformed article: {}", e);
+                    *self.skipped += 1;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// What a value turned out to be instead of the expected one, e.g. "a number".
+type Found = &'static str;
+
+/// Reads any value, telling what it was when it isn't one that `visit_map` or
+/// `visit_str` takes. Arrays and objects are skipped over to get to the next value.
+macro_rules! visit_unexpected {
+    ($wrap:expr) => {
+        fn visit_bool<E: Error>(self, _: bool) -> Result<Self::Value, E> {
+            Ok($wrap("a boolean"))
+        }
+
+        fn visit_i64<E: Error>(self, _: i64) -> Result<Self::Value, E> {
+            Ok($wrap("a number"))
+        }
+
+        fn visit_u64<E: Error>(self, _: u64) -> Result<Self::Value, E> {
+            Ok($wrap("a number"))
+        }
+
+        fn visit_f64<E: Error>(self, _: f64) -> Result<Self::Value, E> {
+            Ok($wrap("a number"))
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+            while seq.next_element::<IgnoredAny>()?.is_some() {}
+            Ok($wrap("an array"))
+        }
+    };
+}
+
+/// A field that should be a string, `Err` saying what it was instead. `null` reads as
+/// `None`, like a missing field.
+struct Text(Result<Option<String>, Found>);
+
+impl<'de> Deserialize<'de> for Text {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(TextVisitor)
+    }
+}
+
+struct TextVisitor;
+
+impl<'de> Visitor<'de> for TextVisitor {
+    type Value = Text;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a string or null")
+    }
+
+    fn visit_str<E: Error>(self, v: &str) -> Result<Text, E> {
+        Ok(Text(Ok(Some(v.to_string()))))
+    }
+
+    fn visit_string<E: Error>(self, v: String) -> Result<Text, E> {
+        Ok(Text(Ok(Some(v))))
+    }
+
+    fn visit_unit<E: Error>(self) -> Result<Text, E> {
+        Ok(Text(Ok(None)))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Text, A::Error> {
+        while map.next_entry::<IgnoredAny, IgnoredAny>()?.is_some() {}
+        Ok(Text(Err("an object")))
+    }
+
+    visit_unexpected!(|found| Text(Err(found)));
+}
+
+#[derive(Deserialize)]
+#[serde(field_identifier, rename_all = "camelCase")]
+enum SourceField {
+    Id,
+    Name,
+    #[serde(other)]
+    Other,
+}
+
+/// An article's `source`, `Err` saying what was wrong with it.
+struct RawSource(Result<Source, String>);
+
+impl<'de> Deserialize<'de> for RawSource {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(SourceVisitor)
+    }
+}
+
+struct SourceVisitor;
+
+impl<'de> Visitor<'de> for SourceVisitor {
+    type Value = RawSource;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a source object")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<RawSource, A::Error> {
+        let mut source = Source::default();
+        let mut problem = None;
+        while let Some(field) = map.next_key()? {
+            match field {
+                SourceField::Id => match map.next_value::<Text>()?.0 {
+                    Ok(id) => source.id = id,
+                    Err(found) => problem = Some(unexpected(found, "source.id")),
+                },
+                // unlike the id, a source always has a name
+                SourceField::Name => match map.next_value::<Text>()?.0 {
+                    Ok(Some(name)) => source.name = name,
+                    Ok(None) => problem = Some(unexpected("null", "source.name")),
+                    Err(found) => problem = Some(unexpected(found, "source.name")),
+                },
+                SourceField::Other => {
+                    map.next_value::<IgnoredAny>()?;
+                }
+            }
+        }
+        Ok(RawSource(problem.map_or(Ok(source), Err)))
+    }
+
+    fn visit_str<E: Error>(self, _: &str) -> Result<RawSource, E> {
+        Ok(RawSource(Err(unexpected("a string", "source"))))
+    }
+
+    fn visit_unit<E: Error>(self) -> Result<RawSource, E> {
+        Ok(RawSource(Err(unexpected("null", "source"))))
+    }
+
+    visit_unexpected!(|found| RawSource(Err(unexpected(found, "source"))));
+}
+
+fn unexpected(found: Found, field: &str) -> String {
+    format!("found {} for `{}`", found, field)
+}
+
+#[derive(Deserialize)]
+#[serde(field_identifier, rename_all = "camelCase")]
+enum ArticleField {
+    Source,
+    Author,
+    Title,
+    Url,
+    Description,
+    Content,
+    UrlToImage,
+    PublishedAt,
+    #[serde(other)]
+    Other,
+}
+
+/// An element of `articles`, `Err` saying why it isn't an [`Article`].
+struct RawArticle(Result<Article, String>);
+
+impl<'de> Deserialize<'de> for RawArticle {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(ArticleVisitor)
+    }
+}
+
+struct ArticleVisitor;
+
+impl<'de> Visitor<'de> for ArticleVisitor {
+    type Value = RawArticle;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("an article object")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<RawArticle, A::Error> {
+        let mut source = Source::default();
+        let [mut author, mut title, mut url, mut description] = [None, None, None, None];
+        let [mut content, mut url_to_image, mut published_at] = [None, None, None];
+        let mut problem = None;
+        while let Some(field) = map.next_key()? {
+            let (slot, name) = match field {
+                ArticleField::Source => {
+                    match map.next_value::<RawSource>()?.0 {
+                        Ok(read) => source = read,
+                        Err(e) => problem = Some(e),
+                    }
+                    continue;
+                }
+                ArticleField::Other => {
+                    map.next_value::<IgnoredAny>()?;
+                    continue;
+                }
+                ArticleField::Author => (&mut author, "author"),
+                ArticleField::Title => (&mut title, "title"),
+                ArticleField::Url => (&mut url, "url"),
+                ArticleField::Description => (&mut description, "description"),
+                ArticleField::Content => (&mut content, "content"),
+                ArticleField::UrlToImage => (&mut url_to_image, "urlToImage"),
+                ArticleField::PublishedAt => (&mut published_at, "publishedAt"),
+            };
+            match map.next_value::<Text>()?.0 {
+                Ok(text) => *slot = text,
+                Err(found) => problem = Some(unexpected(found, name)),
+            }
+        }
+        let article = match (problem, title, url) {
+            (Some(problem), _, _) => Err(problem),
+            (None, None, _) => Err("no `title`".to_string()),
+            (None, _, None) => Err("no `url`".to_string()),
+            (None, Some(title), Some(url)) => Ok(Article {
+                source,
+                author,
+                title,
+                url,
+                description,
+                content,
+                url_to_image,
+                published_at,
+            }),
+        };
+        Ok(RawArticle(article))
+    }
+
+    fn visit_str<E: Error>(self, _: &str) -> Result<RawArticle, E> {
+        Ok(RawArticle(Err("found a string instead of an article".to_string())))
+    }
+
+    fn visit_unit<E: Error>(self) -> Result<RawArticle, E> {
+        Ok(RawArticle(Err("found null instead of an article".to_string())))
+    }
+
+    visit_unexpected!(|found| RawArticle(Err(format!("found {} instead of an article", found))));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn articles_are_handed_over_after_the_total() {
+        let body = r#"{"status": "ok", "totalResults": 40, "articles": [
+            {"title": "One", "url": "https://example.com/1"},
+            {"title": "Two", "url": "https://example.com/2"}
+        ]}"#;
+        let seen = std::cell::RefCell::new(Vec::new());
+        let seed = ResponseSeed {
+            started: &mut |total| seen.borrow_mut().push(format!("total {}", total)),
+            each: &mut |article| seen.borrow_mut().push(article.title),
+        };
+        let response = seed.deserialize(&mut serde_json::Deserializer::from_str(body)).unwrap();
+        assert!(response.articles.is_empty());
+        assert_eq!(seen.into_inner(), ["total 40", "One", "Two"]);
+    }
+
+    #[test]
+    fn malformed_articles_are_skipped_and_counted() {
+        let body = r#"{"status": "ok", "totalResults": 8, "articles": [
+            {"source": {"id": null, "name": "Kept"}, "title": "First", "url": "https://a.com/1",
+             "author": null, "extra": {"nested": [1, {"deep": true}]}},
+            {"title": 42, "url": "https://a.com/2"},
+            {"title": "No url"},
+            "not an article",
+            {"title": "Bad source", "url": "https://a.com/3", "source": "Someone"},
+            {"title": "Bad description", "url": "https://a.com/4", "description": ["x"]},
+            null,
+            {"title": "Second", "url": "https://a.com/5", "publishedAt": "2024-03-01T10:00:00Z"}
+        ]}"#;
+        let response: NewsAPIResponse = serde_json::from_str(body).unwrap();
+        let titles: Vec<&str> = response.articles().iter().map(Article::title).collect();
+        assert_eq!(titles, ["First", "Second"]);
+        assert_eq!(response.skipped(), 6);
+        assert_eq!(response.articles()[0].source(), "Kept");
+        assert_eq!(response.articles()[1].published_at(), Some("2024-03-01T10:00:00Z"));
+    }
+
+    #[test]
+    fn a_body_that_isnt_json_still_fails() {
+        let body = r#"{"status": "ok", "articles": [{"title": "Cut off"#;
+        assert!(serde_json::from_str::<NewsAPIResponse>(body).is_err());
+    }
+}
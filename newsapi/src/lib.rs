@@ -1,28 +1,139 @@
-use serde::Deserialize;
+use std::{fmt, str::FromStr};
+#[cfg(not(target_arch = "wasm32"))]
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::SystemTime,
+};
+
+#[cfg(not(target_arch = "wasm32"))]
+use rustls::{
+    client::{ServerCertVerified, ServerCertVerifier},
+    Certificate, ClientConfig, ServerName,
+};
+
+use chrono::{DateTime, Utc};
+#[cfg(not(target_arch = "wasm32"))]
+use serde::de::DeserializeSeed;
+use serde::{Deserialize, Serialize};
 use url::Url;
 
+#[cfg(not(target_arch = "wasm32"))]
+use decode::ResponseSeed;
+
 #[cfg(feature = "async")]
 use reqwest::Method;
 
-const BASE_URL: &str = "https://newsapi.org/v2";
+mod decode;
 
-#[derive(Deserialize, Debug)]
+/// Where requests go unless [`NewsAPI::base_url`] points them elsewhere.
+pub const BASE_URL: &str = "https://newsapi.org/v2";
+/// Tries per `fetch`, counting the first.
+#[cfg(not(target_arch = "wasm32"))]
+const MAX_ATTEMPTS: u32 = 3;
+/// Wait before the first retry, doubled on each one after.
+#[cfg(not(target_arch = "wasm32"))]
+const RETRY_BASE_MS: u64 = 500;
+/// Sent as `User-Agent` unless [`NewsAPI::user_agent`] says otherwise.
+pub const DEFAULT_USER_AGENT: &str = concat!("newsapi/", env!("CARGO_PKG_VERSION"));
+/// Urls whose last response is kept for conditional requests.
+#[cfg(not(target_arch = "wasm32"))]
+const RESPONSE_CACHE_SIZE: usize = 32;
+
+/// Malformed articles are left out of a response rather than failing it, see
+/// [`skipped`](Self::skipped).
+#[derive(Debug)]
 pub struct NewsAPIResponse {
     status: String,
     articles: Vec<Article>,
     code: Option<String>,
+    total_results: u64,
+    skipped: usize,
+    not_modified: bool,
 }
 
 impl NewsAPIResponse {
     pub fn articles(&self) -> &Vec<Article> {
         &self.articles
     }
+    pub fn total_results(&self) -> u64 {
+        self.total_results
+    }
+    /// Number of malformed articles left out of [`articles`](Self::articles).
+    pub fn skipped(&self) -> usize {
+        self.skipped
+    }
+    /// Whether newsapi answered 304 Not Modified, so this is the previous response again.
+    pub fn not_modified(&self) -> bool {
+        self.not_modified
+    }
+}
+
+/// A publisher newsapi serves, as listed by `/top-headlines/sources`.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct NewsSource {
+    id: String,
+    name: String,
+    /// Two letter codes such as "us" and "en", empty when newsapi leaves them out.
+    #[serde(default)]
+    country: String,
+    #[serde(default)]
+    language: String,
+}
+
+impl NewsSource {
+    /// What goes into [`NewsAPI::sources`].
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    pub fn country(&self) -> &str {
+        &self.country
+    }
+    pub fn language(&self) -> &str {
+        &self.language
+    }
+}
+
+#[derive(Deserialize)]
+struct SourcesResponse {
+    status: String,
+    #[serde(default)]
+    sources: Vec<NewsSource>,
+    code: Option<String>,
+}
+
+impl SourcesResponse {
+    fn into_result(self) -> Result<Vec<NewsSource>, NewsApiError> {
+        match self.status.as_str() {
+            "ok" => Ok(self.sources),
+            _ => Err(map_response_err(self.code)),
+        }
+    }
 }
 
 #[derive(Deserialize, Debug)]
 pub struct Article {
+    #[serde(default)]
+    source: Source,
+    author: Option<String>,
     title: String,
     url: String,
+    description: Option<String>,
+    content: Option<String>,
+    #[serde(rename = "urlToImage")]
+    url_to_image: Option<String>,
+    #[serde(rename = "publishedAt")]
+    published_at: Option<String>,
+}
+
+#[derive(Deserialize, Debug, Default)]
+pub struct Source {
+    id: Option<String>,
+    #[serde(default)]
+    name: String,
 }
 
 impl Article {
@@ -32,12 +143,47 @@ impl Article {
     pub fn url(&self) -> &str {
         &self.url
     }
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+    pub fn content(&self) -> Option<&str> {
+        self.content.as_deref()
+    }
+    pub fn image_url(&self) -> Option<&str> {
+        self.url_to_image.as_deref()
+    }
+    pub fn author(&self) -> Option<&str> {
+        self.author.as_deref()
+    }
+    pub fn source(&self) -> &str {
+        &self.source.name
+    }
+    pub fn source_id(&self) -> Option<&str> {
+        self.source.id.as_deref()
+    }
+    /// ISO 8601 publication time, as sent by newsapi.
+    pub fn published_at(&self) -> Option<&str> {
+        self.published_at.as_deref()
+    }
+    /// The publication time normalized to UTC, `None` when newsapi sent something
+    /// that isn't RFC 3339, so one bad date never fails the whole article.
+    pub fn published_at_utc(&self) -> Option<DateTime<Utc>> {
+        let published_at = self.published_at.as_deref()?;
+        match DateTime::parse_from_rfc3339(published_at.trim()) {
+            Ok(date) => Some(date.with_timezone(&Utc)),
+            Err(e) => {
+                tracing::debug!("unparseable publishedAt {:?}: {}", published_at, e);
+                None
+            }
+        }
+    }
 }
 
 #[derive(thiserror::Error, Debug)]
 pub enum NewsApiError {
+    #[cfg(not(target_arch = "wasm32"))]
     #[error("Failed fetching articles")]
-    RequestFailed(#[from] ureq::Error),
+    RequestFailed(#[from] Box<ureq::Error>),
     #[error("Failed converting response to string")]
     RequestResponseToString(#[from] std::io::Error),
     #[error("Article parsing failed")]
@@ -46,11 +192,78 @@ pub enum NewsApiError {
     UrlParsingError(#[from] url::ParseError),
     #[error("Request failed: {0}")]
     BadRequest(&'static str),
+    /// newsapi doesn't know the key, answered as a 401.
+    #[error("Your API key is invalid")]
+    BadApiKey,
+    /// Too many requests for the key's plan, answered as a 429.
+    #[error("Too many requests, try again later")]
+    RateLimited,
+    /// The check set with [`NewsAPI::cancel_when`] called the fetch off before it was done.
+    #[error("Request cancelled")]
+    Cancelled,
     #[cfg(feature = "async")]
     #[error("Failed fetching articles async")]
     AsyncRequestFailed(#[from] reqwest::Error),
 }
 
+impl NewsApiError {
+    /// Whether trying again could help: 5xx answers and network failures are usually
+    /// transient, anything the server rejected as a 4xx will fail the same way again.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            #[cfg(not(target_arch = "wasm32"))]
+            NewsApiError::RequestFailed(e) => match e.as_ref() {
+                ureq::Error::Status(code, _) => *code >= 500,
+                ureq::Error::Transport(_) => true,
+            },
+            #[cfg(feature = "async")]
+            NewsApiError::AsyncRequestFailed(e) => {
+                e.status().map_or(true, |status| status.is_server_error())
+            }
+            _ => false,
+        }
+    }
+
+    /// The HTTP status the error came with, when it came from an answer at all.
+    pub fn status(&self) -> Option<u16> {
+        match self {
+            #[cfg(not(target_arch = "wasm32"))]
+            NewsApiError::RequestFailed(e) => match e.as_ref() {
+                ureq::Error::Status(code, _) => Some(*code),
+                ureq::Error::Transport(_) => None,
+            },
+            NewsApiError::BadApiKey => Some(401),
+            NewsApiError::RateLimited => Some(429),
+            #[cfg(feature = "async")]
+            NewsApiError::AsyncRequestFailed(e) => e.status().map(|status| status.as_u16()),
+            _ => None,
+        }
+    }
+
+    /// Whether newsapi couldn't be reached at all, as opposed to answering with an error.
+    pub fn is_network_error(&self) -> bool {
+        match self {
+            #[cfg(not(target_arch = "wasm32"))]
+            NewsApiError::RequestFailed(e) => matches!(e.as_ref(), ureq::Error::Transport(_)),
+            #[cfg(feature = "async")]
+            NewsApiError::AsyncRequestFailed(e) => e.is_connect() || e.is_timeout(),
+            _ => false,
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl From<ureq::Error> for NewsApiError {
+    fn from(e: ureq::Error) -> Self {
+        match e {
+            ureq::Error::Status(401, _) => return NewsApiError::BadApiKey,
+            ureq::Error::Status(429, _) => return NewsApiError::RateLimited,
+            _ => {}
+        }
+        NewsApiError::RequestFailed(Box::new(e))
+    }
+}
+
 // pub fn get_articles(url: &str) -> Result<Articles, NewsApiError> {
 //     let response = ureq::get(url)
 //         .call()
@@ -63,32 +276,364 @@ pub enum NewsApiError {
 //     Ok(articles)
 // }
 
+/// Countries newsapi serves top headlines for, by ISO 3166-1 alpha-2 code.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum Country {
+    Ae,
+    Ar,
+    At,
+    Au,
+    Be,
+    Bg,
+    Br,
+    Ca,
+    Ch,
+    Cn,
+    Co,
+    Cu,
+    Cz,
+    De,
+    Eg,
+    Fr,
+    Gb,
+    Gr,
+    Hk,
+    Hu,
+    Id,
+    Ie,
+    Il,
+    In,
+    It,
+    Jp,
+    Kr,
+    Lt,
+    Lv,
+    Ma,
+    Mx,
+    My,
+    Ng,
+    Nl,
+    No,
+    Nz,
+    Ph,
+    Pl,
+    Pt,
+    Ro,
+    Rs,
+    Ru,
+    Sa,
+    Se,
+    Sg,
+    Si,
+    Sk,
+    Th,
+    Tr,
+    Tw,
+    Ua,
+    #[default]
     Us,
+    Ve,
+    Za,
 }
 
-impl ToString for Country {
-    fn to_string(&self) -> String {
+impl Country {
+    pub const ALL: [Country; 54] = [
+        Country::Ae,
+        Country::Ar,
+        Country::At,
+        Country::Au,
+        Country::Be,
+        Country::Bg,
+        Country::Br,
+        Country::Ca,
+        Country::Ch,
+        Country::Cn,
+        Country::Co,
+        Country::Cu,
+        Country::Cz,
+        Country::De,
+        Country::Eg,
+        Country::Fr,
+        Country::Gb,
+        Country::Gr,
+        Country::Hk,
+        Country::Hu,
+        Country::Id,
+        Country::Ie,
+        Country::Il,
+        Country::In,
+        Country::It,
+        Country::Jp,
+        Country::Kr,
+        Country::Lt,
+        Country::Lv,
+        Country::Ma,
+        Country::Mx,
+        Country::My,
+        Country::Ng,
+        Country::Nl,
+        Country::No,
+        Country::Nz,
+        Country::Ph,
+        Country::Pl,
+        Country::Pt,
+        Country::Ro,
+        Country::Rs,
+        Country::Ru,
+        Country::Sa,
+        Country::Se,
+        Country::Sg,
+        Country::Si,
+        Country::Sk,
+        Country::Th,
+        Country::Tr,
+        Country::Tw,
+        Country::Ua,
+        Country::Us,
+        Country::Ve,
+        Country::Za,
+    ];
+
+    /// The lowercase code newsapi takes.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::Ae => "ae",
+            Self::Ar => "ar",
+            Self::At => "at",
+            Self::Au => "au",
+            Self::Be => "be",
+            Self::Bg => "bg",
+            Self::Br => "br",
+            Self::Ca => "ca",
+            Self::Ch => "ch",
+            Self::Cn => "cn",
+            Self::Co => "co",
+            Self::Cu => "cu",
+            Self::Cz => "cz",
+            Self::De => "de",
+            Self::Eg => "eg",
+            Self::Fr => "fr",
+            Self::Gb => "gb",
+            Self::Gr => "gr",
+            Self::Hk => "hk",
+            Self::Hu => "hu",
+            Self::Id => "id",
+            Self::Ie => "ie",
+            Self::Il => "il",
+            Self::In => "in",
+            Self::It => "it",
+            Self::Jp => "jp",
+            Self::Kr => "kr",
+            Self::Lt => "lt",
+            Self::Lv => "lv",
+            Self::Ma => "ma",
+            Self::Mx => "mx",
+            Self::My => "my",
+            Self::Ng => "ng",
+            Self::Nl => "nl",
+            Self::No => "no",
+            Self::Nz => "nz",
+            Self::Ph => "ph",
+            Self::Pl => "pl",
+            Self::Pt => "pt",
+            Self::Ro => "ro",
+            Self::Rs => "rs",
+            Self::Ru => "ru",
+            Self::Sa => "sa",
+            Self::Se => "se",
+            Self::Sg => "sg",
+            Self::Si => "si",
+            Self::Sk => "sk",
+            Self::Th => "th",
+            Self::Tr => "tr",
+            Self::Tw => "tw",
+            Self::Ua => "ua",
+            Self::Us => "us",
+            Self::Ve => "ve",
+            Self::Za => "za",
+        }
+    }
+
+    /// English name, for pickers.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Ae => "United Arab Emirates",
+            Self::Ar => "Argentina",
+            Self::At => "Austria",
+            Self::Au => "Australia",
+            Self::Be => "Belgium",
+            Self::Bg => "Bulgaria",
+            Self::Br => "Brazil",
+            Self::Ca => "Canada",
+            Self::Ch => "Switzerland",
+            Self::Cn => "China",
+            Self::Co => "Colombia",
+            Self::Cu => "Cuba",
+            Self::Cz => "Czechia",
+            Self::De => "Germany",
+            Self::Eg => "Egypt",
+            Self::Fr => "France",
+            Self::Gb => "United Kingdom",
+            Self::Gr => "Greece",
+            Self::Hk => "Hong Kong",
+            Self::Hu => "Hungary",
+            Self::Id => "Indonesia",
+            Self::Ie => "Ireland",
+            Self::Il => "Israel",
+            Self::In => "India",
+            Self::It => "Italy",
+            Self::Jp => "Japan",
+            Self::Kr => "South Korea",
+            Self::Lt => "Lithuania",
+            Self::Lv => "Latvia",
+            Self::Ma => "Morocco",
+            Self::Mx => "Mexico",
+            Self::My => "Malaysia",
+            Self::Ng => "Nigeria",
+            Self::Nl => "Netherlands",
+            Self::No => "Norway",
+            Self::Nz => "New Zealand",
+            Self::Ph => "Philippines",
+            Self::Pl => "Poland",
+            Self::Pt => "Portugal",
+            Self::Ro => "Romania",
+            Self::Rs => "Serbia",
+            Self::Ru => "Russia",
+            Self::Sa => "Saudi Arabia",
+            Self::Se => "Sweden",
+            Self::Sg => "Singapore",
+            Self::Si => "Slovenia",
+            Self::Sk => "Slovakia",
+            Self::Th => "Thailand",
+            Self::Tr => "Turkey",
+            Self::Tw => "Taiwan",
+            Self::Ua => "Ukraine",
+            Self::Us => "United States",
+            Self::Ve => "Venezuela",
+            Self::Za => "South Africa",
+        }
+    }
+
+    /// Looks up a code in either case, `None` for countries newsapi doesn't cover.
+    pub fn from_code(code: &str) -> Option<Country> {
+        let code = code.trim();
+        Country::ALL.into_iter().find(|c| c.code().eq_ignore_ascii_case(code))
+    }
+}
+
+impl fmt::Display for Country {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.code())
+    }
+}
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Category {
+    Business,
+    Entertainment,
+    #[default]
+    General,
+    Health,
+    Science,
+    Sports,
+    Technology,
+}
+
+impl Category {
+    pub const ALL: [Category; 7] = [
+        Category::Business,
+        Category::Entertainment,
+        Category::General,
+        Category::Health,
+        Category::Science,
+        Category::Sports,
+        Category::Technology,
+    ];
+
+    pub fn name(&self) -> &'static str {
         match self {
-            Self::Us => "us".to_string(),
+            Self::Business => "Business",
+            Self::Entertainment => "Entertainment",
+            Self::General => "General",
+            Self::Health => "Health",
+            Self::Science => "Science",
+            Self::Sports => "Sports",
+            Self::Technology => "Technology",
         }
     }
 }
+
+impl fmt::Display for Category {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name().to_lowercase())
+    }
+}
+
+/// A category string newsapi doesn't know, see [`Category::from_str`].
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+#[error("unknown category {0:?}")]
+pub struct ParseCategoryError(String);
+
+/// Parses the names [`Display`](fmt::Display) writes, ignoring case and
+/// surrounding whitespace, so categories round-trip through config and urls.
+impl FromStr for Category {
+    type Err = ParseCategoryError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name = s.trim();
+        Category::ALL
+            .into_iter()
+            .find(|c| c.name().eq_ignore_ascii_case(name))
+            .ok_or_else(|| ParseCategoryError(s.to_string()))
+    }
+}
+
+#[derive(Clone)]
 pub enum Endpoint {
     TopHeadlines,
+    /// Searches every article newsapi indexes; requires a [`query`](NewsAPI::query).
+    Everything,
 }
 
-impl ToString for Endpoint {
-    fn to_string(&self) -> String {
+impl fmt::Display for Endpoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            Self::TopHeadlines => "top-headlines".to_string(),
+            Self::TopHeadlines => write!(f, "top-headlines"),
+            Self::Everything => write!(f, "everything"),
         }
     }
 }
+/// The validators and body of the last successful response for a url, so the next
+/// request can ask whether anything changed rather than download it all again.
+#[cfg(not(target_arch = "wasm32"))]
+struct CachedResponse {
+    etag: Option<String>,
+    last_modified: Option<String>,
+    body: String,
+}
+
+#[derive(Clone)]
 pub struct NewsAPI {
     api_key: String,
     endpoint: Endpoint,
     country: Country,
+    category: Option<Category>,
+    query: Option<String>,
+    sources: Option<String>,
+    page: Option<u32>,
+    page_size: Option<u32>,
+    user_agent: String,
+    base_url: String,
+    #[cfg(not(target_arch = "wasm32"))]
+    accept_invalid_certs: bool,
+    #[cfg(not(target_arch = "wasm32"))]
+    cancelled: Option<Arc<dyn Fn() -> bool + Send + Sync>>,
+    #[cfg(target_arch = "wasm32")]
+    abort_signal: Option<web_sys::AbortSignal>,
+    /// Shared between clones, keyed by request url.
+    #[cfg(not(target_arch = "wasm32"))]
+    cache: Arc<Mutex<HashMap<String, CachedResponse>>>,
 }
 
 impl NewsAPI {
@@ -97,9 +642,30 @@ impl NewsAPI {
             api_key: api_key.to_string(),
             endpoint: Endpoint::TopHeadlines,
             country: Country::Us,
+            category: None,
+            query: None,
+            sources: None,
+            page: None,
+            page_size: None,
+            user_agent: DEFAULT_USER_AGENT.to_string(),
+            base_url: BASE_URL.to_string(),
+            #[cfg(not(target_arch = "wasm32"))]
+            accept_invalid_certs: false,
+            #[cfg(not(target_arch = "wasm32"))]
+            cancelled: None,
+            #[cfg(target_arch = "wasm32")]
+            abort_signal: None,
+            #[cfg(not(target_arch = "wasm32"))]
+            cache: Arc::default(),
         }
     }
 
+    /// Sends requests to another server speaking newsapi's protocol, e.g. a local stub.
+    pub fn base_url(&mut self, base_url: &str) -> &mut NewsAPI {
+        self.base_url = base_url.to_string();
+        self
+    }
+
     pub fn endpoint(&mut self, endpoint: Endpoint) -> &mut NewsAPI {
         self.endpoint = endpoint;
         self
@@ -110,25 +676,280 @@ impl NewsAPI {
         self
     }
 
+    pub fn category(&mut self, category: Category) -> &mut NewsAPI {
+        self.category = Some(category);
+        self
+    }
+
+    /// Keywords or phrases to search for, sent as `q`.
+    pub fn query(&mut self, query: &str) -> &mut NewsAPI {
+        self.query = Some(query.to_string());
+        self
+    }
+
+    /// Comma separated source ids, see [`fetch_sources`](Self::fetch_sources). Top
+    /// headlines then drop the country and category, newsapi won't combine them.
+    pub fn sources(&mut self, sources: &str) -> &mut NewsAPI {
+        self.sources = Some(sources.to_string());
+        self
+    }
+
+    /// Which page of results to fetch, newsapi counts them from 1.
+    pub fn page(&mut self, page: u32) -> &mut NewsAPI {
+        self.page = Some(page);
+        self
+    }
+
+    /// Articles per page, newsapi sends 20 without it and allows up to 100.
+    pub fn page_size(&mut self, page_size: u32) -> &mut NewsAPI {
+        self.page_size = Some(page_size);
+        self
+    }
+
+    /// Identifies the client to newsapi and any proxy on the way. Browsers don't let
+    /// pages change it, so `fetch_web` requests go out with the browser's own.
+    pub fn user_agent(&mut self, user_agent: &str) -> &mut NewsAPI {
+        self.user_agent = user_agent.to_string();
+        self
+    }
+
+    /// Skips TLS certificate validation, which lets requests through proxies that
+    /// re-sign traffic but also through anyone else in the middle. Only for development.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn danger_accept_invalid_certs(&mut self, accept: bool) -> &mut NewsAPI {
+        self.accept_invalid_certs = accept;
+        self
+    }
+
+    /// Checked before every attempt of `fetch` and `fetch_each`. Once it returns true the
+    /// attempts left are skipped and the fetch fails with [`NewsApiError::Cancelled`]. A
+    /// request already sent can't be interrupted, so that one still runs to the end.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn cancel_when(
+        &mut self,
+        cancelled: impl Fn() -> bool + Send + Sync + 'static,
+    ) -> &mut NewsAPI {
+        self.cancelled = Some(Arc::new(cancelled));
+        self
+    }
+
+    /// Signal used to cancel an in-flight `fetch_web` request.
+    #[cfg(target_arch = "wasm32")]
+    pub fn abort_signal(&mut self, signal: web_sys::AbortSignal) -> &mut NewsAPI {
+        self.abort_signal = Some(signal);
+        self
+    }
+
     fn prepare_url(&self) -> Result<String, NewsApiError> {
-        let mut url = Url::parse(BASE_URL)?;
+        let mut url = Url::parse(&self.base_url)?;
         url.path_segments_mut()
-            .unwrap()
+            .map_err(|_| NewsApiError::BadRequest("base url can't take a path"))?
+            .pop_if_empty()
             .push(&self.endpoint.to_string());
 
-        let country = format!("country={}", self.country.to_string());
-        url.set_query(Some(&country));
+        {
+            let mut query = url.query_pairs_mut();
+            // /everything rejects the country and category filters
+            if let (Endpoint::TopHeadlines, None) = (&self.endpoint, &self.sources) {
+                query.append_pair("country", &self.country.to_string());
+                if let Some(category) = &self.category {
+                    query.append_pair("category", &category.to_string());
+                }
+            }
+            if let Some(q) = &self.query {
+                query.append_pair("q", q);
+            }
+            if let Some(sources) = &self.sources {
+                query.append_pair("sources", sources);
+            }
+            if let Some(page) = self.page {
+                query.append_pair("page", &page.to_string());
+            }
+            if let Some(page_size) = self.page_size {
+                query.append_pair("pageSize", &page_size.to_string());
+            }
+        }
 
         Ok(url.to_string())
     }
 
+    fn sources_url(&self) -> Result<String, NewsApiError> {
+        let mut url = Url::parse(&self.base_url)?;
+        url.path_segments_mut()
+            .map_err(|_| NewsApiError::BadRequest("base url can't take a path"))?
+            .pop_if_empty()
+            .extend(["top-headlines", "sources"]);
+        Ok(url.to_string())
+    }
+
+    /// Every source newsapi serves, for picking valid source ids.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn fetch_sources(&self) -> Result<Vec<NewsSource>, NewsApiError> {
+        let url = self.sources_url()?;
+        Self::log_request(&url);
+        let req = self.agent().get(&url).set("Authorization", &self.api_key);
+        let response: SourcesResponse = req.call()?.into_json()?;
+        response.into_result()
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    pub async fn fetch_sources_web(&self) -> Result<Vec<NewsSource>, NewsApiError> {
+        let url = self.sources_url()?;
+        Self::log_request(&url);
+        let response: SourcesResponse = reqwasm::http::Request::get(&url)
+            .header("Authorization", &self.api_key)
+            .send()
+            .await
+            .map_err(|_| NewsApiError::BadRequest("failed sending request"))?
+            .json()
+            .await
+            .map_err(|_| NewsApiError::BadRequest("failed converting response to json"))?;
+        response.into_result()
+    }
+
+    fn log_request(url: &str) {
+        tracing::debug!("requesting {}", url);
+    }
+
+    /// The agent requests go out with, sending the [`user_agent`](Self::user_agent) and
+    /// checking certificates unless told otherwise. Also handy for fetching anything else
+    /// the same way.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn agent(&self) -> ureq::Agent {
+        let builder = ureq::AgentBuilder::new().user_agent(&self.user_agent);
+        if !self.accept_invalid_certs {
+            return builder.build();
+        }
+        tracing::warn!("TLS certificate validation is disabled");
+        builder.tls_config(Arc::new(insecure_tls_config())).build()
+    }
+
+    /// Retries server errors and network failures with exponential backoff, 4xx
+    /// answers such as a bad key come back straight away. Blocks, so browsers use
+    /// [`fetch_web`](Self::fetch_web) instead.
+    #[cfg(not(target_arch = "wasm32"))]
     pub fn fetch(&self) -> Result<NewsAPIResponse, NewsApiError> {
+        let mut articles = Vec::new();
+        let mut response = self.fetch_each(|_| {}, |article| articles.push(article))?;
+        if !response.not_modified {
+            response.articles = articles;
+        }
+        Ok(response)
+    }
+
+    /// Like [`fetch`](Self::fetch), but hands each article to `each` as soon as it's
+    /// decoded rather than collecting them, so the first ones can be shown while the rest
+    /// of a large page is still being read. `started` gets `totalResults` right before
+    /// the first article. The response comes back without articles, except for a 304,
+    /// whose articles are the cached ones and go into the response instead.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn fetch_each(
+        &self,
+        mut started: impl FnMut(u64),
+        mut each: impl FnMut(Article),
+    ) -> Result<NewsAPIResponse, NewsApiError> {
+        let mut attempt = 1;
+        loop {
+            if self.cancelled.as_ref().is_some_and(|cancelled| cancelled()) {
+                return Err(NewsApiError::Cancelled);
+            }
+            // only a request that failed is retried, so nothing was decoded yet
+            match self.fetch_once(&mut started, &mut each) {
+                Err(e) if e.is_retryable() && attempt < MAX_ATTEMPTS => {
+                    let delay = RETRY_BASE_MS << (attempt - 1);
+                    tracing::warn!("{:?}, retrying in {}ms", e, delay);
+                    std::thread::sleep(std::time::Duration::from_millis(delay));
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// A GET for `url`, conditional on the cached response for it when `conditional`.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn request(&self, url: &str, conditional: bool) -> ureq::Request {
+        let mut req = self.agent().get(url).set("Authorization", &self.api_key);
+        let cache = self.cache.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(cached) = cache.get(url).filter(|_| conditional) {
+            if let Some(etag) = &cached.etag {
+                req = req.set("If-None-Match", etag);
+            }
+            if let Some(last_modified) = &cached.last_modified {
+                req = req.set("If-Modified-Since", last_modified);
+            }
+        }
+        req
+    }
+
+    /// Sends `If-None-Match` / `If-Modified-Since` when an earlier response for the same
+    /// url carried an `ETag` / `Last-Modified`, and answers a 304 from that response.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn fetch_once(
+        &self,
+        started: &mut dyn FnMut(u64),
+        each: &mut dyn FnMut(Article),
+    ) -> Result<NewsAPIResponse, NewsApiError> {
         let url = self.prepare_url()?;
-        let req = ureq::get(&url).set("Authorization", &self.api_key);
-        let response: NewsAPIResponse = req.call()?.into_json()?;
+        Self::log_request(&url);
+        let resp = self.request(&url, true).call()?;
+
+        let mut cache = self.cache.lock().unwrap_or_else(|e| e.into_inner());
+        let resp = if resp.status() == 304 {
+            if let Some(cached) = cache.get(&url) {
+                tracing::debug!("{} not modified", url);
+                let mut response: NewsAPIResponse = serde_json::from_str(&cached.body)?;
+                response.not_modified = true;
+                return Ok(response);
+            }
+            // a 304 has no body, and the response it refers to is gone, e.g. the cache
+            // was cleared in between, so ask for the whole response instead
+            tracing::debug!("{} not modified but no longer cached, fetching again", url);
+            drop(cache);
+            let resp = self.request(&url, false).call()?;
+            cache = self.cache.lock().unwrap_or_else(|e| e.into_inner());
+            resp
+        } else {
+            resp
+        };
+        let etag = resp.header("ETag").map(str::to_string);
+        let last_modified = resp.header("Last-Modified").map(str::to_string);
+        let body = resp.into_string()?;
+        // decoding runs on the caller's thread, which for the app is never the UI's
+        let decoding = std::time::Instant::now();
+        let mut decoded = 0;
+        let seed = ResponseSeed {
+            started,
+            each: &mut |article| {
+                decoded += 1;
+                each(article);
+            },
+        };
+        let mut de = serde_json::Deserializer::from_str(&body);
+        let response = seed.deserialize(&mut de)?;
+        de.end()?;
+        tracing::debug!(
+            "decoded {} articles from {} bytes in {:?}",
+            decoded,
+            body.len(),
+            decoding.elapsed()
+        );
 
         match response.status.as_str() {
-            "ok" => Ok(response),
+            "ok" => {
+                if etag.is_some() || last_modified.is_some() {
+                    if cache.len() >= RESPONSE_CACHE_SIZE && !cache.contains_key(&url) {
+                        cache.clear();
+                    }
+                    let cached = CachedResponse {
+                        etag,
+                        last_modified,
+                        body,
+                    };
+                    cache.insert(url, cached);
+                }
+                Ok(response)
+            }
             _ => Err(map_response_err(response.code)),
         }
     }
@@ -136,19 +957,23 @@ impl NewsAPI {
     #[cfg(feature = "async")]
     pub async fn fetch_async(&self) -> Result<NewsAPIResponse, NewsApiError> {
         let url = self.prepare_url()?;
-        let client = reqwest::Client::new();
+        Self::log_request(&url);
+        let builder = reqwest::Client::builder().user_agent(&self.user_agent);
+        #[cfg(not(target_arch = "wasm32"))]
+        let builder = builder.danger_accept_invalid_certs(self.accept_invalid_certs);
+        let client = builder.build().map_err(NewsApiError::AsyncRequestFailed)?;
         let request = client
             .request(Method::GET, url)
             .header("Authorization", &self.api_key)
             .build()
-            .map_err(|e| NewsApiError::AsyncRequestFailed(e))?;
+            .map_err(NewsApiError::AsyncRequestFailed)?;
 
         let response: NewsAPIResponse = client
             .execute(request)
             .await?
             .json()
             .await
-            .map_err(|e| NewsApiError::AsyncRequestFailed(e))?;
+            .map_err(NewsApiError::AsyncRequestFailed)?;
 
         match response.status.as_str() {
             "ok" => Ok(response),
@@ -159,7 +984,10 @@ impl NewsAPI {
     #[cfg(target_arch = "wasm32")]
     pub async fn fetch_web(&self) -> Result<NewsAPIResponse, NewsApiError> {
         let url = self.prepare_url()?;
-        let req = reqwasm::http::Request::get(&url).header("Authorization", &self.api_key);
+        Self::log_request(&url);
+        let req = reqwasm::http::Request::get(&url)
+            .header("Authorization", &self.api_key)
+            .abort_signal(self.abort_signal.as_ref());
         let resp = req
             .send()
             .await
@@ -171,8 +999,8 @@ impl NewsAPI {
             .map_err(|_| NewsApiError::BadRequest("failed converting response to json"))?;
 
         match response.status.as_str() {
-            "ok" => return Ok(response),
-            _ => return Err(map_response_err(response.code)),
+            "ok" => Ok(response),
+            _ => Err(map_response_err(response.code)),
         }
     }
 }
@@ -181,9 +1009,165 @@ fn map_response_err(code: Option<String>) -> NewsApiError {
     if let Some(code) = code {
         match code.as_str() {
             "apiKeyDisabled" => NewsApiError::BadRequest("Your API key has been disabled"),
+            "apiKeyInvalid" | "apiKeyMissing" => NewsApiError::BadApiKey,
+            "rateLimited" => NewsApiError::RateLimited,
             _ => NewsApiError::BadRequest("Unknown error"),
         }
     } else {
         NewsApiError::BadRequest("Unknown error")
     }
 }
+
+/// Accepts whatever certificate the server presents.
+#[cfg(not(target_arch = "wasm32"))]
+struct AcceptAnyCert;
+
+#[cfg(not(target_arch = "wasm32"))]
+impl ServerCertVerifier for AcceptAnyCert {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &Certificate,
+        _intermediates: &[Certificate],
+        _server_name: &ServerName,
+        _scts: &mut dyn Iterator<Item = &[u8]>,
+        _ocsp_response: &[u8],
+        _now: SystemTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        Ok(ServerCertVerified::assertion())
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn insecure_tls_config() -> ClientConfig {
+    ClientConfig::builder()
+        .with_safe_defaults()
+        .with_custom_certificate_verifier(Arc::new(AcceptAnyCert))
+        .with_no_client_auth()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{BufRead, BufReader, Write},
+        net::TcpListener,
+        thread,
+    };
+
+    use chrono::TimeZone;
+
+    use super::*;
+
+    const ONE_ARTICLE: &str = r#"{
+        "status": "ok",
+        "totalResults": 1,
+        "articles": [
+            {"source": {"id": null, "name": "Stub"}, "title": "Hello",
+             "url": "https://example.com/hello"}
+        ]
+    }"#;
+
+    /// Answers one connection per response, in order, and returns the url to reach it at.
+    fn stub_server(responses: Vec<(u16, &'static str)>) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}/v2", listener.local_addr().unwrap());
+        thread::spawn(move || {
+            for (status, body) in responses {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut line = String::new();
+                while reader.read_line(&mut line).unwrap() > 0 && line != "\r\n" {
+                    line.clear();
+                }
+                let head = format!(
+                    "HTTP/1.1 {} Stub\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                    status,
+                    body.len()
+                );
+                stream.write_all(head.as_bytes()).unwrap();
+                stream.write_all(body.as_bytes()).unwrap();
+            }
+        });
+        base_url
+    }
+
+    #[test]
+    fn total_results_is_read_from_the_body() {
+        let response: NewsAPIResponse = serde_json::from_str(ONE_ARTICLE).unwrap();
+        assert_eq!(response.total_results(), 1);
+        let body = r#"{"status": "ok", "totalResults": 9481, "articles": []}"#;
+        let response: NewsAPIResponse = serde_json::from_str(body).unwrap();
+        assert_eq!(response.total_results(), 9481);
+        let body = r#"{"status": "ok", "articles": []}"#;
+        let response: NewsAPIResponse = serde_json::from_str(body).unwrap();
+        assert_eq!(response.total_results(), 0);
+    }
+
+    #[test]
+    fn categories_round_trip_through_strings() {
+        for category in Category::ALL {
+            assert_eq!(category.to_string().parse(), Ok(category));
+            assert_eq!(category.name().parse(), Ok(category));
+        }
+        assert_eq!(" SCIENCE ".parse(), Ok(Category::Science));
+    }
+
+    #[test]
+    fn unknown_categories_fail_to_parse() {
+        for unknown in ["", "politics", "sport", "science,health"] {
+            let parsed = unknown.parse::<Category>();
+            assert_eq!(parsed, Err(ParseCategoryError(unknown.to_string())));
+        }
+    }
+
+    fn published_at(published_at: &str) -> Article {
+        let json = serde_json::json!({ "title": "T", "url": "U", "publishedAt": published_at });
+        serde_json::from_value(json).unwrap()
+    }
+
+    #[test]
+    fn published_at_is_read_as_utc() {
+        let expected = Utc.with_ymd_and_hms(2024, 3, 1, 10, 30, 0).unwrap();
+        assert_eq!(published_at("2024-03-01T10:30:00Z").published_at_utc(), Some(expected));
+        assert_eq!(published_at("2024-03-01T10:30:00+00:00").published_at_utc(), Some(expected));
+        assert_eq!(published_at("2024-03-01T12:30:00+02:00").published_at_utc(), Some(expected));
+        assert_eq!(published_at(" 2024-03-01T10:30:00Z ").published_at_utc(), Some(expected));
+    }
+
+    #[test]
+    fn malformed_published_at_is_none() {
+        for malformed in ["", "yesterday", "2024-03-01", "2024-13-01T10:30:00Z", "10:30 UTC"] {
+            assert_eq!(published_at(malformed).published_at_utc(), None, "{:?}", malformed);
+        }
+    }
+
+    #[test]
+    fn fetch_retries_a_server_error() {
+        let base_url = stub_server(vec![(503, "{}"), (200, ONE_ARTICLE)]);
+        let mut api = NewsAPI::new("key");
+        api.base_url(&base_url);
+        let response = api.fetch().unwrap();
+        assert_eq!(response.articles().len(), 1);
+        assert_eq!(response.articles()[0].title(), "Hello");
+    }
+
+    #[test]
+    fn a_cancelled_fetch_is_not_retried() {
+        let base_url = stub_server(vec![(503, "{}"), (200, ONE_ARTICLE)]);
+        let mut api = NewsAPI::new("key");
+        api.base_url(&base_url);
+        let checks = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let counted = checks.clone();
+        // called off once the first attempt is out
+        api.cancel_when(move || counted.fetch_add(1, std::sync::atomic::Ordering::SeqCst) > 0);
+        assert!(matches!(api.fetch(), Err(NewsApiError::Cancelled)));
+        assert_eq!(checks.load(std::sync::atomic::Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn fetch_gives_up_on_a_client_error() {
+        let base_url = stub_server(vec![(400, "{}"), (200, ONE_ARTICLE)]);
+        let mut api = NewsAPI::new("key");
+        api.base_url(&base_url);
+        assert_eq!(api.fetch().unwrap_err().status(), Some(400));
+    }
+}